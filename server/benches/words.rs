@@ -1,9 +1,13 @@
 use divan::Bencher;
+use letter_boxed::solver::coverage::CoverageState;
+use letter_boxed::solver::index::BoardIndex;
 use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
 use letter_boxed::solver::solutions::{SolveParams, Solver};
+use letter_boxed::solver::strategies::{BestFirst, SearchStrategy, StrategyKind};
 use letter_boxed::solver::words::{
     can_make_word, random_english_string, random_string, WordTrajectory,
 };
+use num::BigUint;
 
 fn main() {
     // Run registered benchmarks.
@@ -58,13 +62,57 @@ fn bench_can_make_word_english<const SIZES: usize>(bencher: Bencher) {
         });
 }
 
-#[divan::bench(threads=true, consts = [4, 16, 64], max_time=1)]
-fn bench_solve<const SIZES: usize>(bencher: Bencher) {
+/// A board of uniformly random letters, cut to each benchmarked size. Boards
+/// drawn with English letter frequencies have so many playable words that a
+/// 64 letter one exhausts memory before it is solved.
+const SOLVE_BOARD: &str = "lkookkihwizqdkfylynzexwilnehemuknnobsyufosssnqznpimgbqouozaaetmd";
+
+/// Benchmark the end to end solve of a board, with node coverage stored in a
+/// fixed-width integer against an arbitrary-width one
+#[divan::bench(types = [u64, BigUint], consts = [4, 16, 64], max_time = 1)]
+fn bench_solve<S: CoverageState, const SIZES: usize>(bencher: Bencher)
+where
+    BestFirst: SearchStrategy<S>,
+{
+    let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+    let params = SolveParams::new(&SOLVE_BOARD[..SIZES]).unwrap();
+    bencher.bench_local(|| {
+        let index: BoardIndex<S> = params.index(&lexicon).unwrap();
+        let _ = Solver::attempt_indexed(&params, &index, &BestFirst);
+    });
+}
+
+/// Benchmark the built-in search strategies against each other
+#[divan::bench(
+    args = [StrategyKind::BestFirst, StrategyKind::Bfs, StrategyKind::AStar, StrategyKind::Beam, StrategyKind::Annealing],
+    max_time = 1
+)]
+fn bench_solve_strategy(bencher: Bencher, strategy: StrategyKind) {
+    let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+    bencher
+        .with_inputs(|| {
+            // Generate a random game board
+            SolveParams {
+                strategy: Some(strategy),
+                ..SolveParams::new(random_english_string(12).as_str()).unwrap()
+            }
+        })
+        .bench_local_values(|params| {
+            let _ = Solver::solve(params, &lexicon);
+        });
+}
+
+#[cfg(feature = "parallel")]
+#[divan::bench(args = [12, 16], max_time = 1)]
+fn bench_solve_parallel(bencher: Bencher, n: usize) {
     let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
     bencher
         .with_inputs(|| {
             // Generate a random game board
-            SolveParams::new(random_english_string(SIZES).as_str()).unwrap()
+            SolveParams {
+                strategy: Some(StrategyKind::ParallelBfs),
+                ..SolveParams::new(random_english_string(n).as_str()).unwrap()
+            }
         })
         .bench_local_values(|params| {
             let _ = Solver::solve(params, &lexicon);
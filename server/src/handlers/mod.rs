@@ -1,18 +1,32 @@
 //! Various handlers for webserver API
 
 use poem::{
-    error::{NotFoundError, ParseQueryError},
+    error::{InternalServerError, NotFoundError, ParseQueryError},
     handler,
     http::StatusCode,
-    web::Query,
+    web::{
+        sse::{Event, SSE},
+        Query,
+    },
     IntoResponse, Response, Result,
 };
 
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
 
 use crate::solver::{
+    hints::{hint, HintLevel},
+    infer::infer_on,
     lexicon::{Lexicon, LEXICON_PATH},
-    solutions::{SolutionResult, SolveParams},
+    solutions::{
+        comma_separated, Optimality, ParamsError, SolutionPath, SolutionResult, SolveParams, Solver,
+    },
+    strategies::{CancelToken, Limits},
+    topology::AdjacencyRule,
+    validate::{validate_play_on, PlayReport},
 };
 
 impl<'a> IntoResponse for SolutionResult<'a> {
@@ -21,12 +35,24 @@ impl<'a> IntoResponse for SolutionResult<'a> {
     }
 }
 
+/// Runs `f` off the async runtime, handing it a token that is cancelled if
+/// the request is dropped (e.g. the client disconnects) before `f` returns
+pub async fn spawn_cancellable<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce(CancelToken) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let cancel = CancelToken::new();
+    let _guard = cancel.drop_guard();
+    tokio::task::spawn_blocking(move || f(cancel))
+        .await
+        .map_err(InternalServerError)
+}
+
 #[handler]
-pub fn handle_solve(res: Result<Query<SolveParams>>) -> Result<impl IntoResponse> {
-    // TODO: figure out how to keep this in app memory instead of reloading
-    let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+pub async fn handle_solve(res: Result<Query<SolveParams>>) -> Result<impl IntoResponse> {
     match res {
-        Ok(Query(params)) => {
+        Ok(Query(mut params)) => {
             // Validate params before sending to solver
             println!("Params: {:?}", params);
             match params.validate() {
@@ -37,10 +63,166 @@ pub fn handle_solve(res: Result<Query<SolveParams>>) -> Result<impl IntoResponse
                         .body(err.to_string()));
                 }
             }
-            let soln = SolutionResult::from_params(params, &lexicon);
-            println!("Solution {:?}", soln);
 
-            return Ok(soln.into_response());
+            let body = spawn_cancellable(move |cancel| {
+                params.cancel = Some(cancel);
+                // TODO: figure out how to keep this in app memory instead of reloading
+                let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+                params.validate_words(&lexicon)?;
+                let soln = SolutionResult::from_params(params, &lexicon);
+                println!("Solution {:?}", soln);
+                Ok::<_, ParamsError>(json!(soln).to_string())
+            })
+            .await?;
+
+            return match body {
+                Ok(body) => Ok(Response::builder().body(body)),
+                Err(err) => Ok(Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(err.to_string())),
+            };
+        }
+        Err(err) if err.is::<ParseQueryError>() => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(err.to_string())),
+        Err(err) => Err(err),
+    }
+}
+
+/// A solution streamed by `handle_solve_stream`, without its graph
+#[derive(Debug, Serialize)]
+struct Improvement {
+    solution: SolutionPath,
+    optimality: Optimality,
+    /// Time since the solve started
+    runtime: Duration,
+}
+
+#[handler]
+pub async fn handle_solve_stream(res: Result<Query<SolveParams>>) -> Result<Response> {
+    match res {
+        Ok(Query(params)) => {
+            if let Err(err) = params.validate() {
+                return Ok(Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(err.to_string()));
+            }
+            // TODO: figure out how to keep this in app memory instead of reloading
+            let (mut params, lexicon, checked) = tokio::task::spawn_blocking(move || {
+                let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+                let checked = params.validate_words(&lexicon);
+                (params, lexicon, checked)
+            })
+            .await
+            .map_err(InternalServerError)?;
+            if let Err(err) = checked {
+                return Ok(Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(err.to_string()));
+            }
+
+            // Each better solution is sent as a `solution` event as soon as it
+            // is found, followed by a single `done` event with the final status
+            let cancel = CancelToken::new();
+            let guard = cancel.drop_guard();
+            params.cancel = Some(cancel);
+            let (tx, rx) = mpsc::unbounded_channel();
+            tokio::task::spawn_blocking(move || {
+                let now = Instant::now();
+                let result = Solver::solve_anytime(params, &lexicon, |solution| {
+                    let improvement = Improvement {
+                        solution: solution.path(),
+                        optimality: solution.optimality(),
+                        runtime: now.elapsed(),
+                    };
+                    let event = Event::message(json!(improvement).to_string());
+                    let _ = tx.send(event.event_type("solution"));
+                });
+                let status = match result {
+                    Ok(_) => json!({ "status": "SUCCESS" }),
+                    Err(err) => json!({ "status": { "FAIL": err } }),
+                };
+                let _ = tx.send(Event::message(status.to_string()).event_type("done"));
+            });
+
+            // The guard lives as long as the stream, so the solve is cancelled
+            // once the client goes away
+            let events = UnboundedReceiverStream::new(rx).map(move |event| {
+                let _ = &guard;
+                event
+            });
+            Ok(SSE::new(events).into_response())
+        }
+        Err(err) if err.is::<ParseQueryError>() => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(err.to_string())),
+        Err(err) => Err(err),
+    }
+}
+
+/// Returns the solve parameters describing just the board of `letters`,
+/// laid out on `sides` under the `adjacency` rule
+fn board_params(
+    letters: &str,
+    sides: Vec<String>,
+    adjacency: AdjacencyRule,
+) -> Result<SolveParams, ParamsError> {
+    Ok(SolveParams {
+        sides,
+        adjacency,
+        ..SolveParams::new(letters)?
+    })
+}
+
+/// Parameters for validating a player's words
+#[derive(Debug, Deserialize)]
+pub struct ValidateParams {
+    pub letters: String,
+    /// Letters of each side of the board, in order (comma separated); four
+    /// sides of equal length if not given
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub sides: Vec<String>,
+    /// Which positions may not be played one after the other
+    #[serde(default)]
+    pub adjacency: AdjacencyRule,
+    /// Words played, in order (comma separated)
+    #[serde(deserialize_with = "comma_separated")]
+    pub words: Vec<String>,
+}
+
+impl IntoResponse for PlayReport {
+    fn into_response(self) -> Response {
+        Response::builder().body(json!(self).to_string())
+    }
+}
+
+#[handler]
+pub async fn handle_validate(res: Result<Query<ValidateParams>>) -> Result<impl IntoResponse> {
+    match res {
+        Ok(Query(params)) => {
+            // The board itself must be valid before any word can be
+            let topology = match board_params(&params.letters, params.sides, params.adjacency)
+                .and_then(|p| {
+                    p.validate()?;
+                    p.topology()
+                }) {
+                Ok(topology) => topology,
+                Err(err) => {
+                    return Ok(Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(err.to_string()));
+                }
+            };
+            // Checking the rules is linear in the words played, and only
+            // solved plays go on to the (memoized) verifier, so validation
+            // always runs to the end
+            let report = spawn_cancellable(move |_| {
+                // TODO: figure out how to keep this in app memory instead of reloading
+                let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+                validate_play_on(&params.letters, &topology, &params.words, &lexicon)
+            })
+            .await?;
+            Ok(report.into_response())
         }
         Err(err) if err.is::<ParseQueryError>() => Ok(Response::builder()
             .status(StatusCode::INTERNAL_SERVER_ERROR)
@@ -49,6 +231,276 @@ pub fn handle_solve(res: Result<Query<SolveParams>>) -> Result<impl IntoResponse
     }
 }
 
+/// Parameters for hinting at the next word of a game
+#[derive(Debug, Deserialize)]
+pub struct HintParams {
+    pub letters: String,
+    /// Letters of each side of the board, in order (comma separated); four
+    /// sides of equal length if not given
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub sides: Vec<String>,
+    /// Which positions may not be played one after the other
+    #[serde(default)]
+    pub adjacency: AdjacencyRule,
+    /// Words played so far, in order (comma separated)
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub played: Vec<String>,
+    /// How much of the next word to give away
+    #[serde(default)]
+    pub level: HintLevel,
+}
+
+#[handler]
+pub async fn handle_hint(res: Result<Query<HintParams>>) -> Result<impl IntoResponse> {
+    match res {
+        Ok(Query(params)) => {
+            let mut solve_params =
+                match board_params(&params.letters, params.sides, params.adjacency) {
+                    Ok(solve_params) => solve_params,
+                    Err(err) => {
+                        return Ok(Response::builder()
+                            .status(StatusCode::BAD_REQUEST)
+                            .body(err.to_string()))
+                    }
+                };
+            solve_params.played = params.played;
+            if let Err(err) = solve_params.validate() {
+                return Ok(Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(err.to_string()));
+            }
+            let level = params.level;
+            let result = spawn_cancellable(move |cancel| {
+                solve_params.cancel = Some(cancel);
+                // TODO: figure out how to keep this in app memory instead of reloading
+                let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+                hint(solve_params, &lexicon, level)
+            })
+            .await?;
+            // A finished game has no next word, so its hint is null
+            match result {
+                Ok(hint) => Ok(Response::builder().body(json!(hint).to_string())),
+                Err(err) => Ok(Response::builder()
+                    .status(StatusCode::UNPROCESSABLE_ENTITY)
+                    .body(json!(err).to_string())),
+            }
+        }
+        Err(err) if err.is::<ParseQueryError>() => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(err.to_string())),
+        Err(err) => Err(err),
+    }
+}
+
+/// Parameters for inferring the unknown letters of a board
+#[derive(Debug, Deserialize)]
+pub struct InferParams {
+    /// The board, with `?` at each unknown position
+    pub letters: String,
+    /// Letters of each side of the board, in order (comma separated); four
+    /// sides of equal length if not given
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub sides: Vec<String>,
+    /// Which positions may not be played one after the other
+    #[serde(default)]
+    pub adjacency: AdjacencyRule,
+    /// Words remembered from a game on the board (comma separated)
+    #[serde(deserialize_with = "comma_separated")]
+    pub words: Vec<String>,
+    /// Maximum number of trajectories to trace before giving up
+    #[serde(default)]
+    pub max_expansions: Option<usize>,
+    /// Maximum time to spend inferring before giving up, in milliseconds
+    #[serde(default = "InferParams::default_max_duration")]
+    pub max_duration: u64,
+}
+
+impl InferParams {
+    fn default_max_duration() -> u64 {
+        5000
+    }
+}
+
+#[handler]
+pub async fn handle_infer(res: Result<Query<InferParams>>) -> Result<impl IntoResponse> {
+    match res {
+        Ok(Query(params)) => {
+            let topology = match board_params(&params.letters, params.sides, params.adjacency)
+                .and_then(|p| {
+                    p.validate()?;
+                    p.topology()
+                }) {
+                Ok(topology) => topology,
+                Err(err) => {
+                    return Ok(Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(err.to_string()));
+                }
+            };
+
+            let result = spawn_cancellable(move |cancel| {
+                let limits = Limits::new(
+                    params.max_expansions,
+                    Some(Duration::from_millis(params.max_duration)),
+                )
+                .with_cancel(Some(cancel));
+                // TODO: figure out how to keep this in app memory instead of reloading
+                let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+                infer_on(&params.letters, &topology, &params.words, &lexicon, &limits)
+            })
+            .await?;
+
+            match result {
+                Ok(inferences) => Ok(Response::builder().body(json!(inferences).to_string())),
+                Err(err) => Ok(Response::builder()
+                    .status(StatusCode::UNPROCESSABLE_ENTITY)
+                    .body(json!(err).to_string())),
+            }
+        }
+        Err(err) if err.is::<ParseQueryError>() => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(err.to_string())),
+        Err(err) => Err(err),
+    }
+}
+
+/// Pagination parameters for enumerating solutions
+#[derive(Debug, Deserialize)]
+pub struct EnumerateParams {
+    /// Maximum number of words in each solution
+    #[serde(default = "EnumerateParams::default_max_words")]
+    pub max_words: usize,
+    /// Zero-based index of the page to return
+    #[serde(default)]
+    pub page: usize,
+    /// Number of solutions per page
+    #[serde(default = "EnumerateParams::default_page_size")]
+    pub page_size: usize,
+}
+
+impl EnumerateParams {
+    /// Most words a solution may be enumerated with; the number of solutions
+    /// grows exponentially with it
+    pub const MAX_WORDS: usize = 4;
+    /// Most solutions a page may hold
+    pub const MAX_PAGE_SIZE: usize = 1000;
+    /// Most solutions that may come before a page, all of which are
+    /// enumerated again to reach it
+    pub const MAX_OFFSET: usize = 10_000;
+
+    fn default_max_words() -> usize {
+        2
+    }
+
+    fn default_page_size() -> usize {
+        50
+    }
+
+    /// Checks the page is within bounds, returning the number of solutions
+    /// before it
+    pub fn validate(&self) -> Result<usize, String> {
+        if self.max_words > Self::MAX_WORDS {
+            return Err(format!(
+                "Solutions can be enumerated with at most {} words",
+                Self::MAX_WORDS
+            ));
+        }
+        if self.page_size > Self::MAX_PAGE_SIZE {
+            return Err(format!(
+                "Pages can hold at most {} solutions",
+                Self::MAX_PAGE_SIZE
+            ));
+        }
+        self.page
+            .checked_mul(self.page_size)
+            .filter(|offset| *offset <= Self::MAX_OFFSET)
+            .ok_or_else(|| format!("Page {} is out of range", self.page))
+    }
+}
+
+/// A single page of enumerated solutions
+#[derive(Debug, Serialize)]
+pub struct SolutionPage {
+    solutions: Vec<SolutionPath>,
+    page: usize,
+    page_size: usize,
+    /// Whether later pages contain more solutions
+    has_more: bool,
+    /// Whether the enumeration ran without hitting a limit, so that a page
+    /// with no more after it really is the last
+    complete: bool,
+}
+
+impl IntoResponse for SolutionPage {
+    fn into_response(self) -> Response {
+        Response::builder().body(json!(self).to_string())
+    }
+}
+
+#[handler]
+pub async fn handle_enumerate(
+    res: Result<Query<SolveParams>>,
+    page_res: Result<Query<EnumerateParams>>,
+) -> Result<impl IntoResponse> {
+    match (res, page_res) {
+        (Ok(Query(mut params)), Ok(Query(page_params))) => {
+            if let Err(err) = params.validate() {
+                return Ok(Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(err.to_string()));
+            }
+            let skipped = match page_params.validate() {
+                Ok(skipped) => skipped,
+                Err(err) => {
+                    return Ok(Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(err));
+                }
+            };
+            // Solutions are enumerated in a deterministic order, so a page is
+            // simply a window over the stream (plus one to detect later pages)
+            let (max_words, page_size) = (page_params.max_words, page_params.page_size);
+            let solutions = spawn_cancellable(move |cancel| {
+                params.cancel = Some(cancel);
+                // TODO: figure out how to keep this in app memory instead of reloading
+                let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+                Solver::enumerate(params, &lexicon, max_words).map(|mut enumeration| {
+                    let solutions: Vec<SolutionPath> = enumeration
+                        .by_ref()
+                        .skip(skipped)
+                        .take(page_size.saturating_add(1))
+                        .collect();
+                    (solutions, enumeration.complete())
+                })
+            })
+            .await?;
+            let (mut solutions, complete) = match solutions {
+                Ok(solutions) => solutions,
+                Err(err) => {
+                    return Ok(Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(err.to_string()));
+                }
+            };
+            let has_more = solutions.len() > page_params.page_size;
+            solutions.truncate(page_params.page_size);
+
+            Ok(SolutionPage {
+                solutions,
+                page: page_params.page,
+                page_size: page_params.page_size,
+                has_more,
+                complete,
+            }
+            .into_response())
+        }
+        (Err(err), _) | (_, Err(err)) if err.is::<ParseQueryError>() => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(err.to_string())),
+        (Err(err), _) | (_, Err(err)) => Err(err),
+    }
+}
+
 #[handler]
 fn return_err() -> Result<&'static str, NotFoundError> {
     Err(NotFoundError)
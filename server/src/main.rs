@@ -1,14 +1,23 @@
 pub mod handlers;
 pub mod solver;
 
-use handlers::handle_solve;
+use handlers::{
+    handle_enumerate, handle_hint, handle_infer, handle_solve, handle_solve_stream, handle_validate,
+};
 use poem::endpoint::EndpointExt;
 use poem::middleware::Cors;
 use poem::{get, listener::TcpListener, Result, Route, Server};
 
-#[tokio::main(flavor = "current_thread")]
+#[cfg_attr(feature = "parallel", tokio::main)]
+#[cfg_attr(not(feature = "parallel"), tokio::main(flavor = "current_thread"))]
 pub async fn main() -> Result<(), std::io::Error> {
-    let app = Route::new().at("/solve", get(handle_solve));
+    let app = Route::new()
+        .at("/solve", get(handle_solve))
+        .at("/solve/stream", get(handle_solve_stream))
+        .at("/enumerate", get(handle_enumerate))
+        .at("/validate", get(handle_validate))
+        .at("/hint", get(handle_hint))
+        .at("/infer", get(handle_infer));
 
     Server::new(TcpListener::bind("0.0.0.0:3000"))
         // TODO: fix CORS requirements
@@ -0,0 +1,281 @@
+//! # Annealing
+//!
+//! A stochastic, anytime search strategy ported from the original Python
+//! solver (`letter_box.solve`).
+//!
+//! Each iteration is a random walk from one of the starting nodes. At every
+//! step the walk looks `depth` words ahead, scores each lookahead path by the
+//! number of new letters it covers and picks one at random: high-scoring paths
+//! are strongly preferred, with noise proportional to the current temperature.
+//! The temperature follows a `Schedule` over the iterations, so early walks
+//! explore and later walks exploit. The walk with the fewest words is kept.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::annealing::{Annealing, Schedule};
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::solutions::{SolveParams, Solver};
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let params = SolveParams::new("rvheaipnwgmo").unwrap();
+//!
+//! let strategy = Annealing::new(42)
+//!     .iterations(100)
+//!     .depth(2)
+//!     .schedule(Schedule::Exponential { start: 1.0, decay: 0.95 });
+//! let result = Solver::try_solve_with(params, &lexicon, &strategy);
+//! println!("{:?}", result.map(|s| s.words().clone()));
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    graph::{Graph, Node, NodeID, SearchOutcome},
+    index::BoardIndex,
+    strategies::{Limits, SearchStrategy},
+};
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+
+/// How the temperature changes over the iterations of an annealing search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule {
+    /// The same temperature for every iteration
+    Constant(f64),
+    /// Linearly interpolate from `start` to `end`
+    Linear { start: f64, end: f64 },
+    /// Multiply the temperature by `decay` every iteration
+    Exponential { start: f64, decay: f64 },
+}
+
+impl Schedule {
+    /// Returns the temperature for the given iteration
+    pub fn temperature(&self, iteration: usize, iterations: usize) -> f64 {
+        match *self {
+            Self::Constant(t) => t,
+            Self::Linear { start, end } => {
+                let progress = iteration as f64 / iterations.max(1) as f64;
+                start + (end - start) * progress
+            }
+            Self::Exponential { start, decay } => start * decay.powi(iteration as i32),
+        }
+    }
+}
+
+/// Simulated-annealing random walks over the graph
+#[derive(Debug, Clone, Copy)]
+pub struct Annealing {
+    seed: u64,
+    iterations: usize,
+    depth: usize,
+    schedule: Schedule,
+}
+
+impl Default for Annealing {
+    fn default() -> Self {
+        Annealing::new(0)
+    }
+}
+
+/// Exponent applied to lookahead scores before sampling, which strongly
+/// favors the paths covering the most new letters
+const SCORE_EXPONENT: i32 = 10;
+
+impl Annealing {
+    /// Creates a new annealing search with the given random seed
+    pub fn new(seed: u64) -> Annealing {
+        Annealing {
+            seed,
+            iterations: 1_000,
+            depth: 1,
+            schedule: Schedule::Linear {
+                start: 1.0,
+                end: 0.0,
+            },
+        }
+    }
+
+    /// Sets the number of random walks to take
+    pub fn iterations(mut self, iterations: usize) -> Annealing {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the number of words to look ahead at every step of a walk
+    pub fn depth(mut self, depth: usize) -> Annealing {
+        self.depth = depth.max(1);
+        self
+    }
+
+    /// Sets the temperature schedule
+    pub fn schedule(mut self, schedule: Schedule) -> Annealing {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Collects every path of up to `depth` words from `node`, stopping early
+    /// at nodes without successors (e.g. full coverage)
+    fn lookahead<S: CoverageState>(
+        &self,
+        node: &Node<S>,
+        successors: &HashMap<NodeID<S>, Vec<Node<S>>>,
+        depth: usize,
+    ) -> Vec<Vec<Node<S>>> {
+        let next = successors.get(node.id()).map_or(&[][..], |s| s.as_slice());
+        if depth == 0 || next.is_empty() {
+            return vec![vec![]];
+        }
+        let mut paths = vec![];
+        for n in next {
+            for mut path in self.lookahead(n, successors, depth - 1) {
+                path.insert(0, n.clone());
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
+impl<S: CoverageState> SearchStrategy<S> for Annealing {
+    fn search<'a>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        let max_score = index.goal();
+        let starts: Vec<Node<S>> = graph.nodes().clone();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut successors: HashMap<NodeID<S>, Vec<Node<S>>> = HashMap::new();
+        let mut best: Option<Vec<NodeID<S>>> = None;
+
+        if starts.is_empty() {
+            return SearchOutcome::Exhausted;
+        }
+
+        for iteration in 0..self.iterations {
+            if let Some(reason) = limits.interruption() {
+                return best.map_or(SearchOutcome::Budget(reason), SearchOutcome::Found);
+            }
+            let temperature = self.schedule.temperature(iteration, self.iterations);
+            let mut node = starts[iteration % starts.len()].clone();
+            let mut path = vec![node.id().clone()];
+
+            while node.score() < max_score {
+                // Expand every node within reach of the lookahead (once)
+                let mut reach = vec![node.clone()];
+                for _ in 0..self.depth {
+                    let mut next_reach = vec![];
+                    for n in reach {
+                        if !successors.contains_key(n.id()) {
+                            if let Some(reason) = limits.stop_reason(successors.len()) {
+                                return best
+                                    .map_or(SearchOutcome::Budget(reason), SearchOutcome::Found);
+                            }
+                            let new = graph.generate_successors(n.id(), index);
+                            successors.insert(n.id().clone(), new);
+                        }
+                        next_reach.extend(successors[n.id()].iter().cloned());
+                    }
+                    reach = next_reach;
+                }
+
+                // Weight each lookahead path by the letters it newly covers,
+                // then blur the weights with temperature-scaled noise
+                let paths: Vec<Vec<Node<S>>> = self
+                    .lookahead(&node, &successors, self.depth)
+                    .into_iter()
+                    .filter(|p| !p.is_empty())
+                    .collect();
+                let mut weights: Vec<f64> = paths
+                    .iter()
+                    .map(|p| {
+                        ((p.last().unwrap().score() - node.score()) as f64).powi(SCORE_EXPONENT)
+                    })
+                    .collect();
+                let total: f64 = weights.iter().sum();
+                if total == 0.0 {
+                    break;
+                }
+                weights
+                    .iter_mut()
+                    .for_each(|w| *w = *w / total + rng.gen::<f64>() * temperature.max(0.0));
+
+                let choice = WeightedIndex::new(&weights).unwrap().sample(&mut rng);
+                for n in &paths[choice] {
+                    path.push(n.id().clone());
+                }
+                node = paths[choice].last().unwrap().clone();
+
+                // Abandon walks that can no longer beat the best so far
+                if best.as_ref().is_some_and(|b| path.len() >= b.len()) {
+                    break;
+                }
+            }
+
+            if node.score() == max_score && best.as_ref().is_none_or(|b| path.len() < b.len()) {
+                best = Some(path);
+            }
+        }
+        best.map_or(SearchOutcome::Exhausted, SearchOutcome::Found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Annealing, Schedule};
+    use crate::solver::{
+        graph::{Graph, SearchOutcome, StopReason},
+        index::BoardIndex,
+        lexicon::{Lexicon, LEXICON_PATH},
+        strategies::{Limits, SearchStrategy},
+    };
+
+    #[test]
+    fn schedules() {
+        let linear = Schedule::Linear {
+            start: 1.0,
+            end: 0.0,
+        };
+        assert_eq!(linear.temperature(0, 10), 1.0);
+        assert_eq!(linear.temperature(5, 10), 0.5);
+        assert_eq!(Schedule::Constant(0.3).temperature(7, 10), 0.3);
+        let exponential = Schedule::Exponential {
+            start: 2.0,
+            decay: 0.5,
+        };
+        assert_eq!(exponential.temperature(2, 10), 0.5);
+    }
+
+    #[test]
+    fn annealing_search() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let index = BoardIndex::new(letters, &lexicon);
+        let strategy = Annealing::new(7).iterations(48).depth(1);
+
+        let mut graph = Graph::from_letters(letters);
+        let SearchOutcome::Found(path) = strategy.search(&mut graph, &index, &Limits::default())
+        else {
+            panic!("No path found");
+        };
+        assert!(path.len() >= 3);
+
+        // The same seed always gives the same result
+        let mut graph = Graph::from_letters(letters);
+        let outcome = strategy.search(&mut graph, &index, &Limits::default());
+        assert_eq!(outcome, SearchOutcome::Found(path));
+    }
+
+    #[test]
+    fn annealing_budget() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let index = BoardIndex::new(letters, &lexicon);
+        let mut graph = Graph::from_letters(letters);
+        let outcome = Annealing::new(7).search(&mut graph, &index, &Limits::expansions(1));
+        assert_eq!(outcome, SearchOutcome::Budget(StopReason::Expansions));
+    }
+}
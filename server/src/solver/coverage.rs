@@ -0,0 +1,193 @@
+//! # Coverage
+//!
+//! Sets of covered letter positions, used as the state of a `Node`.
+//!
+//! Real boards have between 12 and 64 letters, so the solver stores coverage
+//! in a fixed-width integer (`u64` or `u128`) whenever the board fits, and
+//! only falls back to a heap-allocated `BigUint` for huge boards.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::coverage::CoverageState;
+//!
+//! let mut state = u64::empty();
+//! state.set(0);
+//! state.set(3);
+//! assert_eq!(state.count(), 2);
+//! assert_eq!(format!("{:b}", state), "1001");
+//! ```
+
+use super::graph::{AnyGraph, Graph};
+use num::{BigUint, Zero};
+use std::{fmt, hash::Hash};
+
+/// A set of covered letter positions
+pub trait CoverageState: Clone + fmt::Debug + fmt::Binary + Hash + Eq + Send + Sync {
+    /// The number of positions this state can hold, if bounded
+    const CAPACITY: Option<usize>;
+
+    /// Returns a state with no covered positions
+    fn empty() -> Self;
+
+    /// Marks the position `i` as covered
+    fn set(&mut self, i: usize);
+
+    /// Returns whether the position `i` is covered
+    fn contains(&self, i: usize) -> bool;
+
+    /// Returns the number of covered positions
+    fn count(&self) -> usize;
+
+    /// Returns the positions covered by either state
+    fn union(&self, other: &Self) -> Self;
+
+    /// Converts the state into an arbitrary-width integer
+    fn to_biguint(&self) -> BigUint;
+
+    /// Wraps a graph searched with this state, without converting it
+    fn any_graph(graph: Graph<'_, Self>) -> AnyGraph<'_>;
+
+    /// Returns whether a board of `size` positions fits in this state
+    fn fits(size: usize) -> bool {
+        Self::CAPACITY.is_none_or(|capacity| size <= capacity)
+    }
+}
+
+impl CoverageState for u64 {
+    const CAPACITY: Option<usize> = Some(64);
+
+    fn empty() -> Self {
+        0
+    }
+
+    fn set(&mut self, i: usize) {
+        *self |= 1 << i;
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self >> i & 1 == 1
+    }
+
+    fn count(&self) -> usize {
+        self.count_ones() as usize
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        self | other
+    }
+
+    fn to_biguint(&self) -> BigUint {
+        BigUint::from(*self)
+    }
+
+    fn any_graph(graph: Graph<'_, Self>) -> AnyGraph<'_> {
+        AnyGraph::U64(graph)
+    }
+}
+
+impl CoverageState for u128 {
+    const CAPACITY: Option<usize> = Some(128);
+
+    fn empty() -> Self {
+        0
+    }
+
+    fn set(&mut self, i: usize) {
+        *self |= 1 << i;
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self >> i & 1 == 1
+    }
+
+    fn count(&self) -> usize {
+        self.count_ones() as usize
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        self | other
+    }
+
+    fn to_biguint(&self) -> BigUint {
+        BigUint::from(*self)
+    }
+
+    fn any_graph(graph: Graph<'_, Self>) -> AnyGraph<'_> {
+        AnyGraph::U128(graph)
+    }
+}
+
+impl CoverageState for BigUint {
+    const CAPACITY: Option<usize> = None;
+
+    fn empty() -> Self {
+        BigUint::zero()
+    }
+
+    fn set(&mut self, i: usize) {
+        self.set_bit(i as u64, true);
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.bit(i as u64)
+    }
+
+    fn count(&self) -> usize {
+        self.count_ones() as usize
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        self | other
+    }
+
+    fn to_biguint(&self) -> BigUint {
+        self.clone()
+    }
+
+    fn any_graph(graph: Graph<'_, Self>) -> AnyGraph<'_> {
+        AnyGraph::Big(graph)
+    }
+}
+
+/// Returns a state with only position `i` covered
+pub fn singleton<S: CoverageState>(i: usize) -> S {
+    let mut state = S::empty();
+    state.set(i);
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{singleton, CoverageState};
+    use num::{BigUint, FromPrimitive, One};
+
+    fn roundtrip<S: CoverageState>() {
+        let mut state = S::empty();
+        assert_eq!(state.count(), 0);
+        for i in [0, 2, 5, 63] {
+            state.set(i);
+        }
+        assert_eq!(state.count(), 4);
+        assert!(state.contains(5));
+        assert!(!state.contains(4));
+
+        let mut expected = BigUint::from_u64(0b100101).unwrap();
+        expected.set_bit(63, true);
+        assert_eq!(state.to_biguint(), expected);
+        assert_eq!(format!("{:b}", state), format!("{:b}", expected));
+    }
+
+    #[test]
+    fn coverage_states() {
+        roundtrip::<u64>();
+        roundtrip::<u128>();
+        roundtrip::<BigUint>();
+
+        assert!(u64::fits(64));
+        assert!(!u64::fits(65));
+        assert!(u128::fits(128));
+        assert!(BigUint::fits(10_000));
+        assert_eq!(singleton::<u128>(100).to_biguint(), BigUint::one() << 100);
+    }
+}
@@ -0,0 +1,218 @@
+//! # Diagnose
+//!
+//! Explain why a board can't be solved.
+//!
+//! A failed solve only says that no chain of words covers the board. The
+//! diagnosis says why: which letters no playable word touches at all, which
+//! letters can only be reached as the first or last letter of a word, and the
+//! most letters any chain of words manages to cover (with the words that get
+//! there).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::diagnose::diagnose;
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let diagnosis = diagnose("rvheaipnwgmo", &lexicon);
+//! println!("{:?}", diagnosis.untouched());
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    graph::{Graph, SearchOutcome},
+    index::BoardIndex,
+    lexicon::Lexicon,
+    strategies::{Bfs, Limits, SearchStrategy},
+    topology::BoardTopology,
+    words::can_make_word_on,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Why a board can (or can't) be covered
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// Letters no playable word touches, in board order.
+    untouched: Vec<char>,
+    /// Letters only ever reached as the first or last letter of a word.
+    ends_only: Vec<char>,
+    /// The most letters covered by any chain of words.
+    best_coverage: usize,
+    /// A chain of words reaching the best coverage.
+    best_words: Vec<String>,
+    /// Whether every reachable state was explored. If the limits stopped the
+    /// search first, a chain covering more letters may exist.
+    complete: bool,
+}
+
+impl Diagnosis {
+    /// Returns the letters no playable word touches.
+    pub fn untouched(&self) -> &Vec<char> {
+        &self.untouched
+    }
+
+    /// Returns the letters only reached as the first or last letter of a word.
+    pub fn ends_only(&self) -> &Vec<char> {
+        &self.ends_only
+    }
+
+    /// Returns the most letters covered by any chain of words.
+    pub fn best_coverage(&self) -> usize {
+        self.best_coverage
+    }
+
+    /// Returns a chain of words reaching the best coverage.
+    pub fn best_words(&self) -> &Vec<String> {
+        &self.best_words
+    }
+
+    /// Returns whether the best coverage was searched for on the whole board.
+    pub fn complete(&self) -> bool {
+        self.complete
+    }
+}
+
+/// Diagnoses the board of `letters` with the words of `lexicon`.
+///
+/// Finding the best coverage explores every state reachable on the board,
+/// so this is meant for boards a solve has already failed on.
+pub fn diagnose(letters: &str, lexicon: &Lexicon) -> Diagnosis {
+    diagnose_on(
+        letters,
+        BoardTopology::square(letters.chars().count()),
+        lexicon,
+        &Limits::default(),
+    )
+}
+
+/// Diagnoses the board of `letters`, laid out on a board of the given shape,
+/// with the words of `lexicon`.
+///
+/// The search for the best coverage gives up once any of the `limits` is
+/// exceeded, and the diagnosis is marked incomplete.
+pub fn diagnose_on(
+    letters: &str,
+    topology: BoardTopology,
+    lexicon: &Lexicon,
+    limits: &Limits,
+) -> Diagnosis {
+    let index: BoardIndex = BoardIndex::with_topology(letters, topology, lexicon);
+
+    // Retrace each playable word to see where its inner letters fall
+    let mut touched: HashSet<usize> = HashSet::new();
+    let mut inner: HashSet<usize> = HashSet::new();
+    let mut words: HashSet<&str> = HashSet::new();
+    for start in 0..index.size() {
+        for play in index.plays_from(start) {
+            if !words.insert(play.word) {
+                continue;
+            }
+            for trajectory in can_make_word_on(play.word, letters, index.topology()) {
+                let indices = trajectory.indices();
+                touched.extend(indices);
+                if indices.len() > 2 {
+                    inner.extend(&indices[1..indices.len() - 1]);
+                }
+            }
+        }
+    }
+    let untouched = letters
+        .chars()
+        .enumerate()
+        .filter(|(i, _)| !touched.contains(i))
+        .map(|(_, c)| c)
+        .collect();
+    let ends_only = letters
+        .chars()
+        .enumerate()
+        .filter(|(i, _)| touched.contains(i) && !inner.contains(i))
+        .map(|(_, c)| c)
+        .collect();
+
+    // Explore the whole board for the chain covering the most letters
+    let mut graph = Graph::from_letters(letters);
+    let outcome = Bfs.search(&mut graph, &index, limits);
+    let path = graph.best_partial_path().unwrap_or_default();
+    let best_coverage = path
+        .last()
+        .and_then(|id| graph.get_node(id))
+        .map_or(0, |n| n.state().count());
+    let best_words = path
+        .windows(2)
+        .filter_map(|pair| graph.get_edge(&pair[0], &pair[1]))
+        .map(|edge| edge.word.to_string())
+        .collect();
+
+    Diagnosis {
+        untouched,
+        ends_only,
+        best_coverage,
+        best_words,
+        complete: !matches!(outcome, SearchOutcome::Budget(_)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diagnose, diagnose_on};
+    use crate::solver::{
+        lexicon::{Lexicon, LEXICON_PATH},
+        solutions::{SolveParams, Solver},
+        strategies::Limits,
+        topology::BoardTopology,
+        verify::{verify, VerifyError},
+    };
+
+    #[test]
+    fn diagnose_unsolvable() {
+        /*
+        --------------------------------
+          A E I
+        F       Q
+        Y       X
+        W       Z
+          K V J
+        --------------------------------
+        */
+        let letters = "aeiqxzjvkwyf";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        assert!(Solver::solve(SolveParams::new(letters).unwrap(), &lexicon).is_none());
+
+        let diagnosis = diagnose(letters, &lexicon);
+        assert_eq!(diagnosis.untouched(), &vec!['z']);
+        assert_eq!(diagnosis.ends_only(), &vec!['q', 'x']);
+        assert_eq!(diagnosis.best_coverage(), 8);
+        assert!(diagnosis.complete());
+        assert!(!diagnosis.best_words().is_empty());
+        // The best words are a valid chain, just not a covering one
+        assert_eq!(
+            verify(letters, diagnosis.best_words(), &lexicon),
+            Err(VerifyError::Uncovered(
+                ["q", "x", "z", "j"].map(String::from).to_vec()
+            ))
+        );
+    }
+
+    #[test]
+    fn diagnose_solvable() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let diagnosis = diagnose(letters, &lexicon);
+        assert!(diagnosis.untouched().is_empty());
+        assert_eq!(diagnosis.best_coverage(), letters.len());
+    }
+
+    #[test]
+    fn diagnose_within_limits() {
+        let letters = "aeiqxzjvkwyf";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let topology = BoardTopology::square(letters.len());
+        let diagnosis = diagnose_on(letters, topology, &lexicon, &Limits::expansions(1));
+        assert!(!diagnosis.complete());
+        assert!(diagnosis.best_coverage() < 8);
+        // The letters words touch don't depend on the search
+        assert_eq!(diagnosis.untouched(), &vec!['z']);
+    }
+}
@@ -0,0 +1,337 @@
+//! # Enumerate
+//!
+//! Lazily enumerate every solution to a game up to a maximum number of words.
+//!
+//! Solutions are produced by a depth-first search over the same states as the
+//! `Graph` solver: every word in a solution must cover at least one new letter,
+//! and the search stops extending a path as soon as every letter is covered.
+//! The same sequence of words is only ever yielded once, even if it can be
+//! traced across the board in several ways. A game already under way is
+//! enumerated from the states its played words leave it in, and only the
+//! words still to play are yielded.
+//!
+//! The enumeration stops early once any of its `Limits` is hit (each node
+//! whose successors are computed counts as an expansion), after which
+//! `complete` reports that solutions may have been missed.
+//!
+//! Like the solver, the enumeration stores node coverage in any
+//! `CoverageState`; `Solver::enumerate` picks the narrowest one that fits the
+//! board and returns it as an `AnyEnumeration`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::solutions::{SolveParams, Solver};
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let params = SolveParams::new("caitnsolgbre").unwrap();
+//!
+//! for solution in Solver::enumerate(params, &lexicon, 2).unwrap().take(5) {
+//!     println!("{:?}", solution.words());
+//! }
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    graph::Node,
+    index::BoardIndex,
+    lexicon::Lexicon,
+    solutions::SolutionPath,
+    strategies::{CancelToken, Limits},
+    topology::BoardTopology,
+};
+use num::BigUint;
+use std::collections::HashSet;
+
+/// A node on the current search path, along with the moves left to try from it
+#[derive(Debug)]
+struct Frame<'a, S: CoverageState> {
+    node: Node<S>,
+    /// The word played to reach this node (`None` for starting nodes)
+    word: Option<&'a str>,
+    successors: std::vec::IntoIter<(&'a str, Node<S>)>,
+}
+
+/// An iterator over every distinct solution of at most `max_words` words
+#[derive(Debug)]
+pub struct Enumeration<'a, S: CoverageState = BigUint> {
+    index: BoardIndex<'a, S>,
+    max_words: usize,
+    /// Starting nodes that have not been searched yet
+    starts: std::vec::IntoIter<Node<S>>,
+    stack: Vec<Frame<'a, S>>,
+    seen: HashSet<Vec<&'a str>>,
+    /// Limits that end the enumeration once hit
+    limits: Limits,
+    /// Nodes whose successors have been computed so far
+    expansions: usize,
+    /// Whether a limit ended the enumeration before every solution was found
+    stopped: bool,
+}
+
+/// An enumeration kept with whichever coverage state it searches with,
+/// which yields the same solutions for any of them.
+#[derive(Debug)]
+pub enum AnyEnumeration<'a> {
+    /// An enumeration of a board of up to 64 positions.
+    U64(Enumeration<'a, u64>),
+    /// An enumeration of a board of up to 128 positions.
+    U128(Enumeration<'a, u128>),
+    /// An enumeration of a board of any size.
+    Big(Enumeration<'a>),
+}
+
+impl AnyEnumeration<'_> {
+    /// Returns whether every solution yielded so far was yielded without
+    /// hitting a limit, so that an exhausted enumeration found them all
+    pub fn complete(&self) -> bool {
+        match self {
+            AnyEnumeration::U64(enumeration) => enumeration.complete(),
+            AnyEnumeration::U128(enumeration) => enumeration.complete(),
+            AnyEnumeration::Big(enumeration) => enumeration.complete(),
+        }
+    }
+}
+
+impl Iterator for AnyEnumeration<'_> {
+    type Item = SolutionPath;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            AnyEnumeration::U64(enumeration) => enumeration.next(),
+            AnyEnumeration::U128(enumeration) => enumeration.next(),
+            AnyEnumeration::Big(enumeration) => enumeration.next(),
+        }
+    }
+}
+
+impl<'a> Enumeration<'a> {
+    /// Creates a new enumeration over the solutions for the given letters
+    pub fn new(letters: &str, lexicon: &'a Lexicon, max_words: usize) -> Enumeration<'a> {
+        let topology = BoardTopology::square(letters.chars().count());
+        Self::with_topology(letters, topology, lexicon, max_words)
+    }
+
+    /// Creates a new enumeration over the solutions for the given letters,
+    /// laid out on a board of the given shape
+    pub fn with_topology(
+        letters: &str,
+        topology: BoardTopology,
+        lexicon: &'a Lexicon,
+        max_words: usize,
+    ) -> Enumeration<'a> {
+        Self::from_index(
+            BoardIndex::with_topology(letters, topology, lexicon),
+            max_words,
+        )
+    }
+}
+
+impl<'a, S: CoverageState> Enumeration<'a, S> {
+    /// Creates a new enumeration over the solutions on an indexed board
+    pub fn from_index(index: BoardIndex<'a, S>, max_words: usize) -> Enumeration<'a, S> {
+        let starts: Vec<Node<S>> = (0..index.size())
+            .map(|i| {
+                let mut state = S::empty();
+                state.set(i);
+                Node::new(i, state)
+            })
+            .collect();
+        Enumeration {
+            starts: starts.into_iter(),
+            index,
+            max_words,
+            stack: Vec::new(),
+            seen: HashSet::new(),
+            limits: Limits::default(),
+            expansions: 0,
+            stopped: false,
+        }
+    }
+
+    /// Searches from the given nodes (such as the states a game's played
+    /// words leave it in) rather than from every letter, with `max_words`
+    /// counting only the words played after them
+    pub fn with_starts(mut self, starts: Vec<Node<S>>) -> Enumeration<'a, S> {
+        self.starts = starts.into_iter();
+        self
+    }
+
+    /// Ends the enumeration once any of the given limits is hit
+    pub fn with_limits(mut self, limits: Limits) -> Enumeration<'a, S> {
+        self.limits = limits;
+        self
+    }
+
+    /// Ends the enumeration once the given token is cancelled
+    pub fn with_cancel(mut self, cancel: Option<CancelToken>) -> Enumeration<'a, S> {
+        self.limits.cancel = cancel;
+        self
+    }
+
+    /// Returns whether every solution yielded so far was yielded without
+    /// hitting a limit, so that an exhausted enumeration found them all
+    pub fn complete(&self) -> bool {
+        !self.stopped
+    }
+
+    /// Pushes a node onto the search path, computing its successors if
+    /// the path is still allowed to grow
+    fn push(&mut self, node: Node<S>, word: Option<&'a str>) {
+        let successors = if self.stack.len() < self.max_words {
+            self.expansions += 1;
+            self.index.successors(&node)
+        } else {
+            vec![]
+        };
+        self.stack.push(Frame {
+            node,
+            word,
+            successors: successors.into_iter(),
+        });
+    }
+}
+
+impl<'a, S: CoverageState> Iterator for Enumeration<'a, S> {
+    type Item = SolutionPath;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let max_score = self.index.goal();
+        loop {
+            if self.stopped || self.limits.exceeded(self.expansions) {
+                self.stopped = true;
+                return None;
+            }
+
+            // Begin a new search from the next starting node
+            if self.stack.is_empty() {
+                let start = self.starts.next()?;
+                self.push(start, None);
+                continue;
+            }
+
+            let frame = self.stack.last_mut().unwrap();
+            let Some((word, node)) = frame.successors.next() else {
+                // Every move from this node has been tried
+                self.stack.pop();
+                continue;
+            };
+
+            if node.score() == max_score {
+                let mut words: Vec<&'a str> =
+                    self.stack.iter().filter_map(|frame| frame.word).collect();
+                words.push(word);
+                if !self.seen.insert(words.clone()) {
+                    continue;
+                }
+                let mut states: Vec<Node> = self
+                    .stack
+                    .iter()
+                    .map(|frame| frame.node.to_biguint())
+                    .collect();
+                states.push(node.to_biguint());
+                // Bits of required words aren't positions on the board
+                for state in states.iter_mut() {
+                    let mut bits = state.state().clone();
+                    (self.index.size()..max_score).for_each(|b| bits.set_bit(b as u64, false));
+                    *state = Node::new(state.index(), bits);
+                }
+                return Some(SolutionPath::new(
+                    words.into_iter().map(String::from).collect(),
+                    states,
+                ));
+            }
+            self.push(node, Some(word));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnyEnumeration, Enumeration};
+    use crate::solver::{
+        index::BoardIndex,
+        lexicon::{Lexicon, LEXICON_PATH},
+        solutions::{SolveParams, Solver},
+        strategies::{CancelToken, Limits},
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn enumerate_two_words() {
+        // A board with two-word solutions (e.g. "abilities", "scoring")
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        let solutions: Vec<_> = Enumeration::new(letters, &lexicon, 2).collect();
+        assert!(!solutions.is_empty());
+
+        // No duplicates
+        let distinct: HashSet<_> = solutions.iter().map(|s| s.words().clone()).collect();
+        assert_eq!(distinct.len(), solutions.len());
+
+        for solution in &solutions {
+            assert!(solution.words().len() <= 2);
+            assert_eq!(solution.states().len(), solution.words().len() + 1);
+
+            // Every letter is used by some word
+            let used: HashSet<char> = solution.words().iter().flat_map(|w| w.chars()).collect();
+            assert!(letters.chars().all(|c| used.contains(&c)));
+        }
+    }
+
+    #[test]
+    fn enumerate_no_words() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        assert_eq!(Enumeration::new("rvheaipnwgmo", &lexicon, 0).count(), 0);
+    }
+
+    #[test]
+    fn enumerate_limits() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let mut solutions = Enumeration::new("caitnsolgbre", &lexicon, 2);
+        assert!(solutions.by_ref().count() > 0);
+        assert!(solutions.complete());
+
+        // Running out of expansions ends the enumeration, marked incomplete
+        let mut solutions =
+            Enumeration::new("caitnsolgbre", &lexicon, 2).with_limits(Limits::expansions(3));
+        assert!(solutions.by_ref().count() < 3);
+        assert!(!solutions.complete());
+    }
+
+    #[test]
+    fn enumerate_cancelled() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let cancel = CancelToken::new();
+        let mut solutions =
+            Enumeration::new("caitnsolgbre", &lexicon, 2).with_cancel(Some(cancel.clone()));
+        assert!(solutions.next().is_some());
+        cancel.cancel();
+        assert!(solutions.next().is_none());
+        assert!(!solutions.complete());
+    }
+
+    #[test]
+    fn enumerate_coverage_widths() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Every coverage state yields the same solutions, in the same order
+        let big: Vec<_> = Enumeration::new(letters, &lexicon, 2).collect();
+        let narrow: Vec<_> =
+            Enumeration::from_index(BoardIndex::<u64>::new(letters, &lexicon), 2).collect();
+        assert_eq!(narrow.len(), big.len());
+        for (narrow, big) in narrow.iter().zip(&big) {
+            assert_eq!(narrow.words(), big.words());
+            assert_eq!(narrow.states(), big.states());
+        }
+
+        // The solver enumerates a real board with the narrowest state
+        let params = SolveParams::new(letters).unwrap();
+        let solutions = Solver::enumerate(params, &lexicon, 2).unwrap();
+        assert!(matches!(solutions, AnyEnumeration::U64(_)));
+        assert_eq!(solutions.count(), big.len());
+    }
+}
@@ -14,23 +14,23 @@
 //! let mut graph = Graph::from_letters(&letters);
 
 use super::{
+    coverage::{singleton, CoverageState},
+    index::{BoardIndex, Play},
     lexicon::Lexicon,
-    words::{can_make_word, WordTrajectory},
+    strategies::{BestFirst, Bfs, Limits, SearchStrategy},
+    words::WordTrajectory,
 };
-use num::{BigUint, One, Zero};
+use num::BigUint;
 use serde::ser::{SerializeStruct, SerializeTupleStruct};
 use serde::{Serialize, Serializer};
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap, HashSet},
-};
+use std::{cmp::Ordering, collections::HashMap};
 
 /// Represents a unique identifier for a node in the graph.
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
-pub struct NodeID(usize, BigUint);
+pub struct NodeID<S: CoverageState = BigUint>(usize, S);
 
 // Implement Serialize for NodeID to handle big ints
-impl Serialize for NodeID {
+impl<C: CoverageState> Serialize for NodeID<C> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -44,39 +44,89 @@ impl Serialize for NodeID {
 
 /// Represents a node in the graph.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
-pub struct Node {
-    id: NodeID,
+#[serde(bound = "")]
+pub struct Node<S: CoverageState = BigUint> {
+    id: NodeID<S>,
 }
 
 /// Represents a directed edge between nodes in the graph.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
-pub struct Edge<'a> {
-    prev: NodeID,
-    next: NodeID,
+#[serde(bound = "")]
+pub struct Edge<'a, S: CoverageState = BigUint> {
+    prev: NodeID<S>,
+    next: NodeID<S>,
     pub word: &'a str,
 }
 
+/// Outcome of a bounded search over the graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchOutcome<S: CoverageState = BigUint> {
+    /// A path of nodes from a starting node to a node covering every letter.
+    Found(Vec<NodeID<S>>),
+    /// Every reachable node was expanded without covering every letter.
+    Exhausted,
+    /// The search stopped after hitting one of its limits, for the given
+    /// reason.
+    Budget(StopReason),
+}
+
+/// The limit that stopped a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The search expanded as many nodes as it was allowed.
+    Expansions,
+    /// The search ran past its deadline.
+    Timeout,
+    /// The search was cancelled through its token.
+    Cancelled,
+}
+
+/// A graph kept with whichever coverage state it was searched with, which
+/// serializes the same way for any of them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AnyGraph<'a> {
+    /// A graph of a board of up to 64 positions.
+    U64(Graph<'a, u64>),
+    /// A graph of a board of up to 128 positions.
+    U128(Graph<'a, u128>),
+    /// A graph of a board of any size.
+    Big(Graph<'a>),
+}
+
 /// Represents a graph structure.
 #[derive(Debug, Clone)]
-pub struct Graph<'a> {
-    nodes: Vec<Node>,
-    edges: Vec<Edge<'a>>,
-    node_indices: HashMap<NodeID, usize>,
+pub struct Graph<'a, S: CoverageState = BigUint> {
+    nodes: Vec<Node<S>>,
+    edges: Vec<Edge<'a, S>>,
+    node_indices: HashMap<NodeID<S>, usize>,
+}
+
+impl<S: CoverageState> NodeID<S> {
+    /// Converts the ID to one with an arbitrary-width state.
+    pub fn to_biguint(&self) -> NodeID {
+        NodeID(self.0, self.1.to_biguint())
+    }
 }
 
-impl Node {
+impl<S: CoverageState> Node<S> {
     /// Creates a new node with the given index and state.
-    pub fn new(index: usize, state: BigUint) -> Node {
+    pub fn new(index: usize, state: S) -> Node<S> {
         Node {
             id: NodeID(index, state),
         }
     }
 
-    pub fn from_id(node_id: NodeID) -> Node {
+    pub fn from_id(node_id: NodeID<S>) -> Node<S> {
         Node { id: node_id }
     }
+
+    /// Returns a reference to the ID of the node.
+    pub fn id(&self) -> &NodeID<S> {
+        &self.id
+    }
     /// Returns a reference to the state of the node.
-    pub fn state(&self) -> &BigUint {
+    pub fn state(&self) -> &S {
         &self.id.1
     }
 
@@ -86,22 +136,32 @@ impl Node {
     }
 
     /// Generates a new node by transitioning from the current node based on the given trajectory.
-    pub fn transition(&self, traj: WordTrajectory) -> Node {
+    pub fn transition(&self, traj: WordTrajectory) -> Node<S> {
         let mut new_state = self.state().clone();
         for index in traj.indices() {
-            new_state |= BigUint::one() << index;
+            new_state.set(*index);
         }
         // TODO: double iteration over indices
-        Node::new(*traj.indices().last().unwrap(), new_state.clone())
+        Node::new(*traj.indices().last().unwrap(), new_state)
     }
 
     /// Computes and returns the score of the node.
     pub fn score(&self) -> usize {
-        self.state().count_ones() as usize
+        self.state().count()
+    }
+
+    /// Converts the node to one with an arbitrary-width state.
+    pub fn to_biguint(&self) -> Node {
+        Node::from_id(self.id.to_biguint())
+    }
+
+    /// Generates a new node by playing the given word from the current node.
+    pub fn play(&self, play: &Play<S>) -> Node<S> {
+        Node::new(play.end, self.state().union(&play.mask))
     }
 }
 
-impl Ord for Node {
+impl<S: CoverageState> Ord for Node<S> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         let s = self.score();
         let o = other.score();
@@ -115,15 +175,22 @@ impl Ord for Node {
     }
 }
 
-impl PartialOrd for Node {
+impl<S: CoverageState> PartialOrd for Node<S> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
 impl<'a> Graph<'a> {
+    /// Creates a graph from the given letters.
+    pub fn from_letters(letters: &str) -> Graph<'a> {
+        Self::with_letters(letters)
+    }
+}
+
+impl<'a, S: CoverageState> Graph<'a, S> {
     /// Creates a new empty graph.
-    pub fn new() -> Graph<'a> {
+    pub fn new() -> Graph<'a, S> {
         Graph {
             nodes: Vec::new(),
             edges: Vec::new(),
@@ -132,41 +199,41 @@ impl<'a> Graph<'a> {
     }
 
     /// Returns a reference to the nodes in the graph.
-    pub fn nodes(&self) -> &Vec<Node> {
+    pub fn nodes(&self) -> &Vec<Node<S>> {
         &self.nodes
     }
 
     /// Returns a reference to the edges in the graph.
-    pub fn edges(&self) -> &Vec<Edge> {
+    pub fn edges(&self) -> &Vec<Edge<'a, S>> {
         &self.edges
     }
 
-    /// Creates a graph from the given letters.
-    pub fn from_letters(letters: &str) -> Graph<'a> {
-        let mut nodes: Vec<Node> = vec![];
-        let mut node_indices: HashMap<NodeID, usize> = HashMap::new();
-        for (i, _l) in letters.chars().enumerate() {
-            let mut state = BigUint::zero();
-            state.set_bit(i as u64, true);
-            let node = Node::new(i, state);
-            node_indices.insert(node.id.clone(), nodes.len());
-            nodes.push(node);
-        }
-        Graph {
-            nodes: nodes.into_iter().collect(),
-            edges: Vec::new(),
-            node_indices: node_indices,
+    /// Creates a graph from the given letters, storing coverage in `S`.
+    pub fn with_letters(letters: &str) -> Graph<'a, S> {
+        let nodes = (0..letters.chars().count())
+            .map(|i| Node::new(i, singleton(i)))
+            .collect();
+        Graph::with_nodes(nodes)
+    }
+
+    /// Creates a graph whose searches start from the given nodes (for
+    /// instance the state of a game already under way).
+    pub fn with_nodes(nodes: Vec<Node<S>>) -> Graph<'a, S> {
+        let mut g = Graph::new();
+        for node in nodes {
+            g.add_node(&node.id);
         }
+        g
     }
 
     /// Returns a reference to the node with the given ID, if it exists.
-    pub fn get_node(&self, id: &NodeID) -> Option<&Node> {
+    pub fn get_node(&self, id: &NodeID<S>) -> Option<&Node<S>> {
         let index = *self.node_indices.get(id)?;
         self.nodes.get(index)
     }
 
     /// Returns a reference to the edge between the nodes with the given IDs, if it exists.
-    pub fn get_edge(&self, id1: &NodeID, id2: &NodeID) -> Option<&Edge> {
+    pub fn get_edge(&self, id1: &NodeID<S>, id2: &NodeID<S>) -> Option<&Edge<'a, S>> {
         for edge in self.edges() {
             if edge.prev == *id1 && edge.next == *id2 {
                 return Some(edge);
@@ -176,12 +243,12 @@ impl<'a> Graph<'a> {
     }
 
     /// Checks if the graph contains a node with the given ID.
-    pub fn contains(&self, id: &NodeID) -> bool {
+    pub fn contains(&self, id: &NodeID<S>) -> bool {
         self.get_node(&id).is_some()
     }
 
     /// Adds a node to the graph if it doesn't already exist.
-    pub fn add_node(&mut self, id: &NodeID) {
+    pub fn add_node(&mut self, id: &NodeID<S>) {
         if !self.node_indices.contains_key(id) {
             let node = Node { id: id.clone() };
             self.node_indices.insert(id.clone(), self.nodes.len());
@@ -190,7 +257,7 @@ impl<'a> Graph<'a> {
     }
 
     /// Adds an edge to the graph.
-    fn add_edge(&mut self, prev_id: &NodeID, next_id: &NodeID, word: &'a str) {
+    fn add_edge(&mut self, prev_id: &NodeID<S>, next_id: &NodeID<S>, word: &'a str) {
         self.add_node(prev_id);
         self.add_node(next_id);
 
@@ -202,79 +269,126 @@ impl<'a> Graph<'a> {
         self.edges.push(edge);
     }
 
-    /// Generates edges for the given node based on the provided letters and lexicon.
+    /// Generates edges for the given node from the plays in the board index.
+    ///
+    /// Returns only the nodes that were not already part of the graph.
     pub fn generate_edges_for_node(
         &mut self,
-        id: &NodeID,
-        letters: &str,
-        lexicon: &'a Lexicon,
-    ) -> Option<Vec<Node>> {
-        let node = self.get_node(&id).unwrap().clone();
-        let index = node.index();
-        let cur_char = letters.chars().nth(index).unwrap();
-        let possible_words = lexicon.words_starting_with(cur_char);
+        id: &NodeID<S>,
+        index: &BoardIndex<'a, S>,
+    ) -> Option<Vec<Node<S>>> {
         let n_len = self.nodes().len();
+        self.generate_successors(id, index);
+        let new_nodes = self.nodes()[n_len..].to_vec();
+        Some(new_nodes)
+    }
 
-        for word in possible_words {
-            let possible_trajectories = can_make_word(word, letters);
-            for trajectory in possible_trajectories {
-                let new_node = node.transition(trajectory);
-                if new_node.score() > node.score() {
-                    self.add_node(&new_node.id);
-                    self.add_edge(&node.id, &new_node.id, word);
-                    break;
-                }
+    /// Adds edges from the given node to successors found outside of the
+    /// graph (for instance on another thread).
+    ///
+    /// Returns only the nodes that were not already part of the graph.
+    pub fn add_successors(
+        &mut self,
+        id: &NodeID<S>,
+        successors: Vec<(&'a str, Node<S>)>,
+    ) -> Vec<Node<S>> {
+        let mut new_nodes = vec![];
+        for (word, node) in successors {
+            if !self.contains(node.id()) {
+                new_nodes.push(node.clone());
             }
+            self.add_edge(id, node.id(), word);
         }
-        let new_nodes = self.nodes()[n_len..].to_vec();
-        Some(new_nodes)
+        new_nodes
+    }
+
+    /// Generates edges for the given node from the plays in the board index.
+    ///
+    /// A word that can be traced across the board in several ways gets one
+    /// edge per distinct node it can reach, so the search is free to pick
+    /// whichever trajectory covers the most letters or ends in the best place.
+    ///
+    /// Returns every node reachable from the given node, whether or not
+    /// it was already part of the graph.
+    pub fn generate_successors(
+        &mut self,
+        id: &NodeID<S>,
+        index: &BoardIndex<'a, S>,
+    ) -> Vec<Node<S>> {
+        let node = self.get_node(id).unwrap().clone();
+        let mut successors = vec![];
+
+        for (word, new_node) in index.successors(&node) {
+            self.add_node(&new_node.id);
+            self.add_edge(&node.id, &new_node.id, word);
+            successors.push(new_node);
+        }
+        successors
     }
 
     /// Finds the path of nodes with maximum score based on the given letters and lexicon.
-    pub fn get_node_path(&mut self, letters: &str, lexicon: &'a Lexicon) -> Option<Vec<NodeID>> {
-        let max_score = letters.len();
-        let mut queue: BinaryHeap<Node> = BinaryHeap::new();
-        let binding = self.clone();
-        for node in binding.nodes() {
-            queue.push(node.clone());
+    pub fn get_node_path(&mut self, letters: &str, lexicon: &'a Lexicon) -> Option<Vec<NodeID<S>>> {
+        let index = BoardIndex::new(letters, lexicon);
+        match BestFirst.search(self, &index, &Limits::default()) {
+            SearchOutcome::Found(path) => Some(path),
+            _ => None,
         }
+    }
+
+    /// Finds a path of nodes covering every letter with the fewest possible words.
+    ///
+    /// The search expands the graph one word at a time (breadth first), so the
+    /// first full-coverage node reached is guaranteed to use the fewest words.
+    /// If `max_expansions` is given, the search gives up once that many nodes
+    /// have been expanded and returns `SearchOutcome::Budget`.
+    pub fn get_min_node_path(
+        &mut self,
+        letters: &str,
+        lexicon: &'a Lexicon,
+        max_expansions: Option<usize>,
+    ) -> SearchOutcome<S> {
+        let index = BoardIndex::new(letters, lexicon);
+        Bfs.search(self, &index, &Limits::new(max_expansions, None))
+    }
 
-        let mut visited: HashSet<NodeID> = HashSet::new();
-        let mut parents: HashMap<NodeID, NodeID> = HashMap::new();
+    /// Returns a path to the node with the highest score explored so far
+    /// (the first one found, on ties), for searches that stop early.
+    ///
+    /// The path follows the first edge that reached each node, back to a
+    /// starting node.
+    pub fn best_partial_path(&self) -> Option<Vec<NodeID<S>>> {
+        let best = self
+            .nodes
+            .iter()
+            .reduce(|best, n| if n.score() > best.score() { n } else { best })?;
+        let mut parents: HashMap<NodeID<S>, NodeID<S>> = HashMap::new();
+        for edge in &self.edges {
+            parents
+                .entry(edge.next.clone())
+                .or_insert_with(|| edge.prev.clone());
+        }
+        Some(Graph::trace_path(&parents, best.id()))
+    }
 
-        while let Some(node) = queue.pop() {
-            if visited.contains(&node.id) {
-                continue;
-            }
-            visited.insert(node.id.clone());
-
-            let new_nodes = self
-                .generate_edges_for_node(&node.id, letters, lexicon)
-                .unwrap();
-
-            new_nodes.iter().for_each(|n| {
-                parents.insert(n.id.clone(), node.id.clone());
-                queue.push(n.clone())
-            });
-
-            if node.score() == max_score {
-                let mut parent = &node.id;
-                let mut parents_vec = vec![parent.clone()];
-                while let Some(p) = parents.get(&parent) {
-                    parents_vec.push(p.clone());
-                    parent = &p;
-                }
-                parents_vec.reverse();
-                return Some(parents_vec);
-            }
+    /// Walks the parent links back from `id` and returns the path from its root.
+    pub(crate) fn trace_path(
+        parents: &HashMap<NodeID<S>, NodeID<S>>,
+        id: &NodeID<S>,
+    ) -> Vec<NodeID<S>> {
+        let mut parent = id;
+        let mut parents_vec = vec![parent.clone()];
+        while let Some(p) = parents.get(parent) {
+            parents_vec.push(p.clone());
+            parent = p;
         }
-        None
+        parents_vec.reverse();
+        parents_vec
     }
 }
 
 // Omit node_indices from the serialization of the graph as it is
 // simply an implementation detail
-impl<'a> Serialize for Graph<'a> {
+impl<'a, C: CoverageState> Serialize for Graph<'a, C> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -292,6 +406,7 @@ mod tests {
 
     use crate::solver::{
         graph::NodeID,
+        index::BoardIndex,
         lexicon::{Lexicon, LEXICON_PATH},
         words::WordTrajectory,
     };
@@ -403,7 +518,8 @@ mod tests {
 
         let letters = "uigaangbpiam";
         let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
-        g.generate_edges_for_node(&n0, letters, &lexicon);
+        let index = BoardIndex::new(letters, &lexicon);
+        g.generate_edges_for_node(&n0, &index);
         // Let's make sure the NodeIDs are correct
         for edge in g.edges() {
             let next_node = g.get_node(&edge.next).unwrap();
@@ -425,6 +541,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn repeated_letter_trajectories() {
+        // Same board as above: "map" can reach the P through either A
+        let letters = "uigaangbpiam";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let index: BoardIndex<u64> = BoardIndex::new(letters, &lexicon);
+
+        // Having already covered the M and the first A, only the path
+        // through the second A covers everything "map" can
+        let n0 = NodeID(11, 0b1000_0000_1000u64);
+        let mut g = Graph::new();
+        g.add_node(&n0);
+        let successors = g.generate_successors(&n0, &index);
+
+        let maps: Vec<u64> = g
+            .edges()
+            .iter()
+            .filter(|edge| edge.word == "map")
+            .map(|edge| *g.get_node(&edge.next).unwrap().state())
+            .collect();
+        assert_eq!(maps.len(), 2);
+        assert!(maps.contains(&0b1001_0000_1000));
+        assert!(maps.contains(&0b1001_0001_1000));
+        assert_eq!(successors.len(), g.edges().len());
+    }
+
     #[test]
     fn node_ordering() {
         // The ordering here should be: n0 -> n1 -> n2 -> n3
@@ -476,6 +618,11 @@ mod tests {
         let node_json = json!(n0);
         assert_eq!(json!({"id":[0,"10"]}), node_json);
 
+        // Fixed-width states serialize just like big ints
+        assert_eq!(json!(Node::new(0, 2u64)), node_json);
+        assert_eq!(json!(Node::new(0, 2u128)), node_json);
+        assert_eq!(Node::new(0, 2u64).to_biguint(), n0);
+
         let n1 = Node {
             id: NodeID(0, BigUint::from_usize(256).unwrap()),
         };
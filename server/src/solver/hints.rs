@@ -0,0 +1,190 @@
+//! # Hints
+//!
+//! Hints towards the next word of a game in progress.
+//!
+//! The game is solved from the state the played words leave it in, and the
+//! first word of that solution is hinted at. Each `HintLevel` gives away more
+//! of the word than the last: its first letter and length, then a pattern
+//! with every other letter masked, then the letters it newly covers and
+//! finally the word itself.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::hints::{hint, HintLevel};
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::solutions::SolveParams;
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let mut params = SolveParams::new("rvheaipnwgmo").unwrap();
+//! params.played = vec!["improving".to_string()];
+//!
+//! let hint = hint(params, &lexicon, HintLevel::Pattern).unwrap();
+//! println!("{:?}", hint.and_then(|h| h.pattern().cloned()));
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    lexicon::Lexicon,
+    solutions::{SolutionError, SolveParams, Solver},
+};
+use serde::{Deserialize, Serialize};
+
+/// How much of the next word a hint gives away
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum HintLevel {
+    /// The word's first letter and length
+    #[default]
+    Start,
+    /// The word with every other letter masked, such as `m_g_`
+    Pattern,
+    /// The letters the word covers for the first time
+    Covers,
+    /// The word itself
+    Word,
+}
+
+/// A hint towards the next word, with everything up to its level filled in
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Hint {
+    level: HintLevel,
+    start: char,
+    length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    /// Tiles covered for the first time by the word, in board order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    covers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    word: Option<String>,
+}
+
+impl Hint {
+    /// Returns how much of the word the hint gives away.
+    pub fn level(&self) -> HintLevel {
+        self.level
+    }
+
+    /// Returns the first letter of the word.
+    pub fn start(&self) -> char {
+        self.start
+    }
+
+    /// Returns the number of letters in the word.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the masked pattern of the word, from `HintLevel::Pattern` up.
+    pub fn pattern(&self) -> Option<&String> {
+        self.pattern.as_ref()
+    }
+
+    /// Returns the tiles the word newly covers, from `HintLevel::Covers` up.
+    pub fn covers(&self) -> Option<&Vec<String>> {
+        self.covers.as_ref()
+    }
+
+    /// Returns the word, at `HintLevel::Word`.
+    pub fn word(&self) -> Option<&String> {
+        self.word.as_ref()
+    }
+}
+
+/// Masks every other letter of `word`, starting from the second
+fn mask(word: &str) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| if i % 2 == 0 { c } else { '_' })
+        .collect()
+}
+
+/// Hints at the next word of the game described by `params` (its board and
+/// the words played so far), giving away as much as `level` allows.
+///
+/// Returns `None` if the played words already solve the game, and the
+/// solver's error if the game can't be finished from where it stands.
+pub fn hint(
+    params: SolveParams,
+    lexicon: &Lexicon,
+    level: HintLevel,
+) -> Result<Option<Hint>, SolutionError> {
+    let tiles = params.tiles();
+    let solution = Solver::try_solve(params, lexicon)?;
+    let (Some(word), [before, after, ..]) = (solution.words().first(), &solution.states()[..])
+    else {
+        return Ok(None);
+    };
+
+    let covers = tiles
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| after.state().contains(*i) && !before.state().contains(*i))
+        .map(|(_, tile)| tile.clone())
+        .collect();
+    Ok(Some(Hint {
+        level,
+        start: word.chars().next().unwrap_or_default(),
+        length: word.chars().count(),
+        pattern: (level >= HintLevel::Pattern).then(|| mask(word)),
+        covers: (level >= HintLevel::Covers).then_some(covers),
+        word: (level >= HintLevel::Word).then(|| word.clone()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hint, mask, HintLevel};
+    use crate::solver::{
+        lexicon::{Lexicon, LEXICON_PATH},
+        solutions::SolveParams,
+        validate::validate_play,
+    };
+
+    #[test]
+    fn graded_hints() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let params = || {
+            let mut params = SolveParams::new(letters).unwrap();
+            params.played = vec!["improving".to_string()];
+            params
+        };
+
+        // Each level gives away more of the same word
+        let start = hint(params(), &lexicon, HintLevel::Start).unwrap().unwrap();
+        assert_eq!(start.start(), 'g');
+        assert!(start.pattern().is_none() && start.covers().is_none());
+
+        let pattern = hint(params(), &lexicon, HintLevel::Pattern)
+            .unwrap()
+            .unwrap();
+        assert_eq!(pattern.pattern().unwrap().len(), start.length());
+        assert!(pattern.covers().is_none() && pattern.word().is_none());
+
+        let full = hint(params(), &lexicon, HintLevel::Word).unwrap().unwrap();
+        let word = full.word().unwrap();
+        assert_eq!(full.pattern(), Some(&mask(word)));
+        assert_eq!(word.chars().next(), Some(start.start()));
+
+        // The hinted word is playable and covers what it claims to
+        let report = validate_play(letters, &["improving", word.as_str()], &lexicon);
+        assert!(report.valid());
+        let covered: Vec<String> = report.words()[1]
+            .newly_covered()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(full.covers(), Some(&covered));
+
+        // Nothing to hint at once the game is over
+        let mut solved = SolveParams::new(letters).unwrap();
+        solved.played = ["improving", "german", "nowhere"]
+            .map(String::from)
+            .to_vec();
+        assert!(hint(solved, &lexicon, HintLevel::Word).unwrap().is_none());
+
+        assert_eq!(mask("magi"), "m_g_");
+    }
+}
@@ -0,0 +1,343 @@
+//! # Index
+//!
+//! A per-board index of every playable word.
+//!
+//! Building a `BoardIndex` traces every word of the lexicon across the board
+//! once. For each starting position it lists the words that can be played
+//! from there, along with every distinct position they can end on and the
+//! positions they cover. Expanding a node during a search is then a table
+//! lookup, and the same index can be reused for any number of solves of the
+//! same board.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::index::BoardIndex;
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let index: BoardIndex<u64> = BoardIndex::new("uigaangbpiam", &lexicon);
+//!
+//! for play in index.plays_from(11) {
+//!     println!("{} ends at {} covering {:b}", play.word, play.end, play.mask);
+//! }
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    graph::{Node, NodeID},
+    lexicon::Lexicon,
+    topology::BoardTopology,
+    words::{can_make_word_on, can_make_word_tiles, WILDCARD},
+};
+use num::BigUint;
+use std::collections::{HashMap, HashSet};
+
+/// A word that can be played from some starting position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Play<'a, S: CoverageState = BigUint> {
+    /// The word played.
+    pub word: &'a str,
+    /// The position the word ends on.
+    pub end: usize,
+    /// The positions the word covers (including its first and last).
+    pub mask: S,
+    /// The number of wildcards the word is spelled with.
+    pub wildcards: usize,
+    /// The number of positions played, counting any played twice.
+    pub steps: usize,
+}
+
+/// Every playable word on a board, by starting position
+#[derive(Debug, Clone)]
+pub struct BoardIndex<'a, S: CoverageState = BigUint> {
+    letters: String,
+    /// The tile at each position (a single letter on a classic board).
+    tiles: Vec<String>,
+    topology: BoardTopology,
+    plays: Vec<Vec<Play<'a, S>>>,
+    /// Whether successors may only cover positions not covered before.
+    exact: bool,
+    /// The number of words that must be played, each covering its own bit
+    /// after the board's positions.
+    required: usize,
+}
+
+impl<'a, S: CoverageState> BoardIndex<'a, S> {
+    /// Builds the index of every word in `lexicon` playable on `letters`.
+    ///
+    /// Plays from each position keep the lexicon's word order, and the
+    /// distinct outcomes of a word keep the order its trajectories are found.
+    /// On a board with wildcards, plays spelled with fewer of them come
+    /// first, so searches prefer them.
+    pub fn new(letters: &str, lexicon: &'a Lexicon) -> BoardIndex<'a, S> {
+        let topology = BoardTopology::square(letters.chars().count());
+        Self::with_topology(letters, topology, lexicon)
+    }
+
+    /// Builds the index of every word in `lexicon` playable on `letters`,
+    /// laid out on a board of the given shape.
+    pub fn with_topology(
+        letters: &str,
+        topology: BoardTopology,
+        lexicon: &'a Lexicon,
+    ) -> BoardIndex<'a, S> {
+        Self::build(letters.to_string(), None, topology, lexicon)
+    }
+
+    /// Builds the index of every word in `lexicon` playable on a board of
+    /// tiles, each of which may hold several letters (such as "qu"). Each
+    /// tile is a single position, covered as a whole.
+    pub fn with_tiles(
+        tiles: Vec<String>,
+        topology: BoardTopology,
+        lexicon: &'a Lexicon,
+    ) -> BoardIndex<'a, S> {
+        Self::build(tiles.concat(), Some(tiles), topology, lexicon)
+    }
+
+    fn build(
+        letters: String,
+        tiles: Option<Vec<String>>,
+        topology: BoardTopology,
+        lexicon: &'a Lexicon,
+    ) -> BoardIndex<'a, S> {
+        let tiled = tiles.is_some();
+        let tiles = tiles.unwrap_or_else(|| letters.chars().map(String::from).collect());
+        let mut plays: Vec<Vec<Play<'a, S>>> = vec![vec![]; tiles.len()];
+        let first_letters: HashSet<char> = tiles.iter().filter_map(|t| t.chars().next()).collect();
+        // A wildcard can start a word with any letter
+        let mut first_letters: Vec<char> = if first_letters.contains(&WILDCARD) {
+            ('a'..='z').collect()
+        } else {
+            first_letters.into_iter().collect()
+        };
+        first_letters.sort();
+
+        for c in first_letters {
+            for word in lexicon.words_starting_with(c) {
+                let mut seen: HashMap<(usize, usize, S), usize> = HashMap::new();
+                let trajectories = if tiled {
+                    can_make_word_tiles(word, &tiles, &topology)
+                } else {
+                    can_make_word_on(word, &letters, &topology)
+                };
+                for trajectory in trajectories {
+                    let start = trajectory.indices()[0];
+                    let end = trajectory.last().unwrap();
+                    let mut mask = S::empty();
+                    for i in trajectory.indices() {
+                        mask.set(*i);
+                    }
+                    let wildcards = trajectory.substitutions().len();
+                    let steps = trajectory.len();
+                    match seen.get(&(start, end, mask.clone())) {
+                        // Keep the fewest wildcards and steps an outcome can
+                        // be had with
+                        Some(at) => {
+                            let play = &mut plays[start][*at];
+                            play.wildcards = play.wildcards.min(wildcards);
+                            play.steps = play.steps.min(steps);
+                        }
+                        None => {
+                            seen.insert((start, end, mask.clone()), plays[start].len());
+                            plays[start].push(Play {
+                                word,
+                                end,
+                                mask,
+                                wildcards,
+                                steps,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        for from in plays.iter_mut() {
+            from.sort_by_key(|play| play.wildcards);
+        }
+        BoardIndex {
+            letters,
+            tiles,
+            topology,
+            plays,
+            exact: false,
+            required: 0,
+        }
+    }
+
+    /// Drops every play of a word `keep` returns false for.
+    pub fn retain_words<F: Fn(&str) -> bool>(mut self, keep: F) -> Self {
+        for from in self.plays.iter_mut() {
+            from.retain(|play| keep(play.word));
+        }
+        self
+    }
+
+    /// Requires each of `words` to be played: playing the `j`th covers an
+    /// extra bit after the board's positions, which a solution must cover
+    /// like any position.
+    pub fn require_words<W: AsRef<str>>(mut self, words: &[W]) -> Self {
+        let size = self.size();
+        for from in self.plays.iter_mut() {
+            for play in from.iter_mut() {
+                if let Some(j) = words.iter().position(|w| w.as_ref() == play.word) {
+                    play.mask.set(size + j);
+                }
+            }
+        }
+        self.required = words.len();
+        self
+    }
+
+    /// Restricts successors to exact covers: plays that cover no position
+    /// twice, besides the one they start on (shared with the previous word).
+    pub fn exact_cover(mut self) -> Self {
+        self.exact = true;
+        self
+    }
+
+    /// Returns whether successors are restricted to exact covers.
+    pub fn exact(&self) -> bool {
+        self.exact
+    }
+
+    /// Returns the letters of the board.
+    pub fn letters(&self) -> &str {
+        &self.letters
+    }
+
+    /// Returns the tile at each position of the board.
+    pub fn tiles(&self) -> &Vec<String> {
+        &self.tiles
+    }
+
+    /// Returns the shape of the board.
+    pub fn topology(&self) -> &BoardTopology {
+        &self.topology
+    }
+
+    /// Returns whether any tile of the board is a wildcard.
+    pub fn has_wildcards(&self) -> bool {
+        self.tiles.iter().any(|t| t.starts_with(WILDCARD))
+    }
+
+    /// Returns the number of positions on the board.
+    pub fn size(&self) -> usize {
+        self.plays.len()
+    }
+
+    /// Returns the number of words that must be played.
+    pub fn required(&self) -> usize {
+        self.required
+    }
+
+    /// Returns the score of a solution: every position, plus a bit for each
+    /// required word.
+    pub fn goal(&self) -> usize {
+        self.size() + self.required
+    }
+
+    /// Returns every play starting at the given position.
+    pub fn plays_from(&self, start: usize) -> &[Play<'a, S>] {
+        &self.plays[start]
+    }
+
+    /// Returns the most positions covered by any single play.
+    pub fn max_play_coverage(&self) -> usize {
+        self.plays
+            .iter()
+            .flatten()
+            .map(|play| play.mask.count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns whether a play covers nothing twice: no position within the
+    /// word, and nothing `node` covered besides the position it starts on.
+    fn covers_exactly(&self, node: &Node<S>, play: &Play<'a, S>, new_node: &Node<S>) -> bool {
+        let covered = play.mask.count();
+        // The bit of a required word is no position played
+        let required = (self.size()..self.goal()).any(|b| play.mask.contains(b));
+        play.steps == covered - required as usize && new_node.score() == node.score() + covered - 1
+    }
+
+    /// Returns every distinct node reachable by playing one word from `node`,
+    /// paired with the word played. Only plays that cover a new letter are
+    /// kept, and for an exact cover only those covering nothing twice.
+    pub fn successors(&self, node: &Node<S>) -> Vec<(&'a str, Node<S>)> {
+        self.weighted_successors(node)
+            .into_iter()
+            .map(|(word, new_node, _)| (word, new_node))
+            .collect()
+    }
+
+    /// Returns the same successors as `successors`, each with the fewest
+    /// wildcards the word can reach it with.
+    pub fn weighted_successors(&self, node: &Node<S>) -> Vec<(&'a str, Node<S>, usize)> {
+        let mut successors = vec![];
+        let mut seen: HashSet<(&'a str, NodeID<S>)> = HashSet::new();
+        // Plays are sorted by wildcards, so the first to reach a node is
+        // the one with the fewest
+        for play in self.plays_from(node.index()) {
+            let new_node = node.play(play);
+            if self.exact && !self.covers_exactly(node, play, &new_node) {
+                continue;
+            }
+            if new_node.score() > node.score() && seen.insert((play.word, new_node.id().clone())) {
+                successors.push((play.word, new_node, play.wildcards));
+            }
+        }
+        successors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoardIndex;
+    use crate::solver::{
+        coverage::CoverageState,
+        lexicon::{Lexicon, LEXICON_PATH},
+        words::can_make_word,
+    };
+
+    #[test]
+    fn board_index() {
+        /*
+        --------------------------------
+          U I G
+        M       A
+        A       A
+        I       N
+          P B G
+        --------------------------------
+        */
+        let letters = "uigaangbpiam";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let index: BoardIndex<u64> = BoardIndex::new(letters, &lexicon);
+        assert_eq!(index.size(), 12);
+        assert_eq!(index.letters(), letters);
+
+        // "map" starts at the M and can end at the only P through either A
+        let maps: Vec<_> = index
+            .plays_from(11)
+            .iter()
+            .filter(|play| play.word == "map")
+            .collect();
+        assert_eq!(maps.len(), 2);
+        for play in maps {
+            assert_eq!(play.end, 8);
+            assert_eq!(play.mask.count(), 3);
+        }
+
+        // Every play starts at its position and spells its word
+        for start in 0..index.size() {
+            for play in index.plays_from(start) {
+                assert!(play.mask.contains(start));
+                assert!(play.mask.contains(play.end));
+                assert!(can_make_word(play.word, letters)
+                    .any(|t| t.indices()[0] == start && t.last() == Some(play.end)));
+            }
+        }
+    }
+}
@@ -0,0 +1,374 @@
+//! # Infer
+//!
+//! Recover the unknown letters of a partially remembered board.
+//!
+//! Unknown positions are written as wildcards (`?`). Each remembered word is
+//! traced across the board with `WordTrajectories`, and every trajectory
+//! records the letters its wildcards stood for. Unlike a wildcard in play, an
+//! unknown position holds one letter for the whole game, so an assignment
+//! must give each position the same letter in every word.
+//!
+//! The words are taken on their own: they needn't chain or cover the board,
+//! since a player may remember only some of what they played.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::infer::infer;
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let inferences = infer("rvh?aipnwgm?", &["improving", "nowhere"], &lexicon).unwrap();
+//! for inference in inferences.inferences() {
+//!     println!("{} {:?}", inference.board(), inference.assignments());
+//! }
+//! ```
+
+use super::{
+    lexicon::Lexicon,
+    strategies::Limits,
+    topology::BoardTopology,
+    words::{can_make_word_tiles, WordTrajectories, WILDCARD},
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Reasons the remembered words can't say anything about a board
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum InferError {
+    /// The word isn't in the lexicon.
+    NotInLexicon(String),
+    /// The word can't be traced on the board, whatever the unknown letters.
+    Untraceable(String),
+    /// The board has more unknown positions than `MAX_UNKNOWNS`.
+    TooManyUnknowns(usize),
+}
+
+impl fmt::Display for InferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotInLexicon(word) => write!(f, "{} is not in the lexicon", word),
+            Self::Untraceable(word) => write!(f, "{} can't be traced on the board", word),
+            Self::TooManyUnknowns(count) => write!(
+                f,
+                "{} unknown letters is too many, at most {} can be inferred",
+                count, MAX_UNKNOWNS
+            ),
+        }
+    }
+}
+
+/// Letters for some of the unknown positions that let every remembered word
+/// be played
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Inference {
+    /// The letter at each position the words pin down, by position.
+    assignments: Vec<(usize, char)>,
+    /// The board with those letters filled in (positions the words say
+    /// nothing about stay unknown).
+    board: String,
+}
+
+impl Inference {
+    /// Returns the letter at each position the words pin down, by position.
+    pub fn assignments(&self) -> &Vec<(usize, char)> {
+        &self.assignments
+    }
+
+    /// Returns the board with the assigned letters filled in.
+    pub fn board(&self) -> &String {
+        &self.board
+    }
+}
+
+/// Letters for unknown positions, by position
+type Assignment = BTreeMap<usize, char>;
+
+/// Merges two assignments, unless they give a position different letters
+fn merge(a: &Assignment, b: &Assignment) -> Option<Assignment> {
+    let mut merged = a.clone();
+    for (position, letter) in b {
+        if *merged.entry(*position).or_insert(*letter) != *letter {
+            return None;
+        }
+    }
+    Some(merged)
+}
+
+/// Returns the assignment a trajectory's wildcards make, unless it plays
+/// the same wildcard as two different letters
+fn assignment_of(substitutions: &[(usize, char)]) -> Option<Assignment> {
+    let mut assignment = Assignment::new();
+    for (position, letter) in substitutions {
+        if *assignment.entry(*position).or_insert(*letter) != *letter {
+            return None;
+        }
+    }
+    Some(assignment)
+}
+
+/// Most unknown positions a board may have. Each remembered word is traced
+/// through every unknown position that could stand for each of its letters,
+/// so the work grows exponentially with them.
+pub const MAX_UNKNOWNS: usize = 6;
+
+/// Most inferences returned for one board. A board with mostly unknown
+/// letters allows far more assignments than anyone could read through.
+pub const MAX_INFERENCES: usize = 100;
+
+/// The inferences found for a board
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Inferences {
+    inferences: Vec<Inference>,
+    /// Whether every inference was found, rather than stopping at
+    /// `MAX_INFERENCES` or one of the limits.
+    complete: bool,
+}
+
+impl Inferences {
+    /// Returns the inferences, ordered by board.
+    pub fn inferences(&self) -> &Vec<Inference> {
+        &self.inferences
+    }
+
+    /// Returns whether every inference was found.
+    pub fn complete(&self) -> bool {
+        self.complete
+    }
+}
+
+/// Infers the unknown (`?`) letters of `letters` from words remembered from
+/// a game on it.
+pub fn infer<W: AsRef<str>>(
+    letters: &str,
+    words: &[W],
+    lexicon: &Lexicon,
+) -> Result<Inferences, InferError> {
+    let topology = BoardTopology::square(letters.chars().count());
+    infer_on(letters, &topology, words, lexicon, &Limits::default())
+}
+
+/// State of the search for assignments across the remembered words
+struct Search<'a> {
+    topology: &'a BoardTopology,
+    limits: &'a Limits,
+    /// Trajectories traced so far
+    expansions: usize,
+    found: BTreeSet<Assignment>,
+    /// Whether the search stopped before trying every assignment
+    stopped: bool,
+}
+
+impl Search<'_> {
+    /// Extends `assignment` so that `words` can be traced on `tiles` (the
+    /// board with `assignment` filled in), one word at a time.
+    ///
+    /// Filling in a word's letters before tracing the next one prunes every
+    /// trajectory that disagrees with it, and a word that can be traced
+    /// without any unknown letter fills in none, since any assignment found
+    /// otherwise would only add letters to one found this way.
+    fn extend<W: AsRef<str>>(&mut self, tiles: &[String], words: &[W], assignment: &Assignment) {
+        let Some((word, rest)) = words.split_first() else {
+            self.found.insert(assignment.clone());
+            self.stopped |= self.found.len() >= MAX_INFERENCES;
+            return;
+        };
+        let word = word.as_ref();
+
+        let known: Vec<String> = tiles
+            .iter()
+            .map(|t| {
+                if t.starts_with(WILDCARD) {
+                    String::new()
+                } else {
+                    t.clone()
+                }
+            })
+            .collect();
+        if can_make_word_tiles(word, &known, self.topology)
+            .next()
+            .is_some()
+        {
+            self.extend(tiles, rest, assignment);
+            return;
+        }
+
+        let mut tried = BTreeSet::new();
+        for trajectory in WordTrajectories::with_tiles(word, tiles, self.topology) {
+            self.expansions += 1;
+            if self.stopped || self.limits.exceeded(self.expansions) {
+                self.stopped = true;
+                return;
+            }
+            let Some(letters) = assignment_of(trajectory.substitutions()) else {
+                continue;
+            };
+            if trajectory.len() == 0 || !tried.insert(letters.clone()) {
+                continue;
+            }
+            let Some(merged) = merge(assignment, &letters) else {
+                continue;
+            };
+            let mut filled = tiles.to_vec();
+            for (position, letter) in &letters {
+                filled[*position] = letter.to_string();
+            }
+            self.extend(&filled, rest, &merged);
+        }
+    }
+}
+
+/// Infers the unknown (`?`) letters of `letters`, laid out on a board of the
+/// given shape, from words remembered from a game on it.
+///
+/// Returns every assignment the words allow, ordered by board. Only the
+/// least an assignment needs is kept: when a word can be traced without
+/// some unknown position, no assignment fills it in just for that word, so
+/// each board consistent with the words is the filling in of some
+/// inference. No inferences means the words contradict each other.
+///
+/// The number of assignments can grow exponentially with the number of
+/// unknown positions, so boards with more than `MAX_UNKNOWNS` are refused,
+/// and the search stops at `MAX_INFERENCES` or once any
+/// of the `limits` is exceeded (each trajectory traced counts as an
+/// expansion), returning what it found marked incomplete.
+pub fn infer_on<W: AsRef<str>>(
+    letters: &str,
+    topology: &BoardTopology,
+    words: &[W],
+    lexicon: &Lexicon,
+    limits: &Limits,
+) -> Result<Inferences, InferError> {
+    let unknowns = letters.chars().filter(|c| *c == WILDCARD).count();
+    if unknowns > MAX_UNKNOWNS {
+        return Err(InferError::TooManyUnknowns(unknowns));
+    }
+    let tiles: Vec<String> = letters.chars().map(String::from).collect();
+
+    for word in words {
+        let word = word.as_ref();
+        if !lexicon.contains(word) {
+            return Err(InferError::NotInLexicon(word.to_string()));
+        }
+        let traceable = WordTrajectories::with_tiles(word, &tiles, topology)
+            .any(|t| t.len() > 0 && assignment_of(t.substitutions()).is_some());
+        if !traceable {
+            return Err(InferError::Untraceable(word.to_string()));
+        }
+    }
+
+    let mut search = Search {
+        topology,
+        limits,
+        expansions: 0,
+        found: BTreeSet::new(),
+        stopped: false,
+    };
+    search.extend(&tiles, words, &Assignment::new());
+    let combined = search.found;
+
+    // Drop assignments that only add letters to another
+    let minimal: Vec<&Assignment> = combined
+        .iter()
+        .filter(|a| {
+            !combined
+                .iter()
+                .any(|b| b.len() < a.len() && b.iter().all(|(p, l)| a.get(p) == Some(l)))
+        })
+        .collect();
+
+    let mut inferences: Vec<Inference> = minimal
+        .into_iter()
+        .map(|assignment| Inference {
+            assignments: assignment.iter().map(|(p, l)| (*p, *l)).collect(),
+            board: letters
+                .chars()
+                .enumerate()
+                .map(|(i, c)| match c {
+                    WILDCARD => *assignment.get(&i).unwrap_or(&WILDCARD),
+                    c => c,
+                })
+                .collect(),
+        })
+        .collect();
+    inferences.sort_by(|a, b| a.board.cmp(&b.board));
+    Ok(Inferences {
+        inferences,
+        complete: !search.stopped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{infer, infer_on, InferError, MAX_INFERENCES};
+    use crate::solver::{
+        lexicon::{Lexicon, LEXICON_PATH},
+        strategies::Limits,
+        topology::BoardTopology,
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn infer_unknown_letters() {
+        // March 12 '24, remembering all but the E and O
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let found = infer("rvh?aipnwgm?", &["improving", "nowhere"], &lexicon).unwrap();
+        assert!(found.complete());
+        let inferences = found.inferences();
+        let boards: Vec<&String> = inferences.iter().map(|i| i.board()).collect();
+        assert_eq!(boards, ["rvheaipnwgmo", "rvhoaipnwgme"]);
+        assert_eq!(inferences[0].assignments(), &vec![(3, 'e'), (11, 'o')]);
+
+        // A word without unknown letters says nothing about them
+        let found = infer("rvh?aipnwgm?", &["pig"], &lexicon).unwrap();
+        let inferences = found.inferences();
+        assert_eq!(inferences.len(), 1);
+        assert_eq!(inferences[0].board(), "rvh?aipnwgm?");
+
+        // Words needing different letters in the same place contradict
+        // each other, and unknown words say nothing at all
+        assert!(infer("rvheaipnwgm?", &["improving", "help"], &lexicon)
+            .unwrap()
+            .inferences()
+            .is_empty());
+        assert_eq!(
+            infer("rvh?aipnwgm?", &["qzx"], &lexicon),
+            Err(InferError::NotInLexicon("qzx".to_string()))
+        );
+    }
+
+    #[test]
+    fn infer_mostly_unknown_board() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Far too many unknowns are refused up front
+        let start = Instant::now();
+        assert_eq!(
+            infer("????????????", &["improving"], &lexicon),
+            Err(InferError::TooManyUnknowns(12))
+        );
+
+        // Half the board unknown still allows more boards than are returned
+        let found = infer("rv??ai??wg??", &["improving"], &lexicon).unwrap();
+        assert!(!found.complete());
+        assert!(found.inferences().len() <= MAX_INFERENCES);
+        assert!(!found.inferences().is_empty());
+
+        // The limits stop the search early
+        let topology = BoardTopology::square(12);
+        let limits = Limits::expansions(10);
+        let found = infer_on(
+            "rv??ai??wg??",
+            &topology,
+            &["improving", "nowhere"],
+            &lexicon,
+            &limits,
+        )
+        .unwrap();
+        assert!(!found.complete());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -90,6 +90,23 @@ impl Lexicon {
         &self.data[index]
     }
 
+    /// Returns whether the lexicon contains the given (lowercase) word.
+    pub fn contains(&self, word: &str) -> bool {
+        match word.chars().next() {
+            Some(l @ 'a'..='z') => self.words_starting_with(l).iter().any(|w| w == word),
+            _ => false,
+        }
+    }
+
+    /// Returns the length of the longest word in the lexicon.
+    pub fn max_word_len(&self) -> usize {
+        self.data
+            .iter()
+            .flat_map(|v| v.iter().map(|w| w.len()))
+            .max()
+            .unwrap_or(0)
+    }
+
     /// Return a vector of all words
     pub fn all(&self) -> Vec<String> {
         self.data.iter().flat_map(|v| v.iter().cloned()).collect()
@@ -105,5 +122,9 @@ mod tests {
         let words = Lexicon::new(LEXICON_PATH).unwrap();
         let words_starting_with_a = words.words_starting_with('a');
         assert!(!words_starting_with_a.is_empty());
+        assert!(words.contains("map"));
+        assert!(!words.contains("mapz"));
+        assert!(!words.contains("Map"));
+        assert!(!words.contains(""));
     }
 }
@@ -1,6 +1,21 @@
 // Solver Exports
 
+pub mod annealing;
+pub mod coverage;
+pub mod diagnose;
+pub mod enumerate;
 pub mod graph;
+pub mod hints;
+pub mod index;
+pub mod infer;
 pub mod lexicon;
+pub mod objectives;
+pub mod pangrams;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod solutions;
+pub mod strategies;
+pub mod topology;
+pub mod validate;
+pub mod verify;
 pub mod words;
@@ -0,0 +1,189 @@
+//! # Objectives
+//!
+//! Ways of ranking solutions against each other.
+//!
+//! An `Objective` assigns a cost to a solution, where a lower cost is a better
+//! solution. Objectives can be passed to `Solver::top_k` by value, by
+//! reference or as a boxed trait object.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::objectives::{FewestLetters, Objective};
+//! use letter_boxed::solver::solutions::{SolveParams, Solver};
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let params = SolveParams::new("caitnsolgbre").unwrap();
+//!
+//! let objective: Box<dyn Objective> = Box::new(FewestLetters);
+//! for ranked in Solver::top_k(params, &lexicon, 2, 3, objective).unwrap() {
+//!     println!("{} {:?}", ranked.cost(), ranked.solution().words());
+//! }
+//! ```
+
+use super::{solutions::SolutionPath, topology::BoardTopology};
+use std::collections::HashMap;
+
+/// A cost function over solutions (lower is better)
+pub trait Objective {
+    /// Returns the cost of the given solution
+    fn cost(&self, solution: &SolutionPath) -> f64;
+}
+
+impl<O: Objective + ?Sized> Objective for &O {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        (**self).cost(solution)
+    }
+}
+
+impl<O: Objective + ?Sized> Objective for Box<O> {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        (**self).cost(solution)
+    }
+}
+
+/// Prefer solutions with the fewest words
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FewestWords;
+
+impl Objective for FewestWords {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        solution.words().len() as f64
+    }
+}
+
+/// Prefer solutions with the fewest letters across all words
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FewestLetters;
+
+impl Objective for FewestLetters {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        solution.words().iter().map(|w| w.len()).sum::<usize>() as f64
+    }
+}
+
+/// Prefer solutions whose longest word is as short as possible
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShortestLongestWord;
+
+impl Objective for ShortestLongestWord {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        solution.words().iter().map(|w| w.len()).max().unwrap_or(0) as f64
+    }
+}
+
+/// Prefer solutions made of common words
+///
+/// The cost of a solution is the negative log-likelihood of its words under
+/// the given relative frequencies, so common words (and fewer of them) are
+/// preferred. Words without a frequency are treated as having `fallback`.
+#[derive(Debug, Clone)]
+pub struct MostCommonWords {
+    frequencies: HashMap<String, f64>,
+    fallback: f64,
+}
+
+impl MostCommonWords {
+    /// Creates a new objective from word frequencies
+    pub fn new(frequencies: HashMap<String, f64>, fallback: f64) -> MostCommonWords {
+        MostCommonWords {
+            frequencies,
+            fallback,
+        }
+    }
+
+    /// Creates a new objective from words ordered from most to least common,
+    /// assuming word frequencies follow Zipf's law
+    pub fn from_ranked<I: IntoIterator<Item = String>>(words: I) -> MostCommonWords {
+        let frequencies: HashMap<String, f64> = words
+            .into_iter()
+            .enumerate()
+            .map(|(rank, word)| (word, 1.0 / (rank + 1) as f64))
+            .collect();
+        let fallback = 1.0 / (frequencies.len() + 1) as f64;
+        MostCommonWords::new(frequencies, fallback)
+    }
+}
+
+impl Objective for MostCommonWords {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        solution
+            .words()
+            .iter()
+            .map(|w| -self.frequencies.get(w).unwrap_or(&self.fallback).ln())
+            .sum()
+    }
+}
+
+/// Prefer solutions that play the fewest letters more than once, ranking
+/// perfect solutions first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeastReuse;
+
+impl Objective for LeastReuse {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        solution.reuse() as f64
+    }
+}
+
+/// Prefer solutions that play the fewest wildcards of the given board
+#[derive(Debug, Clone)]
+pub struct FewestWildcards {
+    tiles: Vec<String>,
+    topology: BoardTopology,
+}
+
+impl FewestWildcards {
+    /// Creates a new objective for the board of the given tiles and shape
+    pub fn new(tiles: Vec<String>, topology: BoardTopology) -> FewestWildcards {
+        FewestWildcards { tiles, topology }
+    }
+}
+
+impl Objective for FewestWildcards {
+    fn cost(&self, solution: &SolutionPath) -> f64 {
+        solution.substitutions(&self.tiles, &self.topology).len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FewestLetters, FewestWords, MostCommonWords, Objective, ShortestLongestWord};
+    use crate::solver::solutions::SolutionPath;
+
+    fn path(words: &[&str]) -> SolutionPath {
+        SolutionPath::new(words.iter().map(|w| w.to_string()).collect(), vec![])
+    }
+
+    #[test]
+    fn objective_costs() {
+        let short = path(&["abilities", "scoring"]);
+        let long = path(&["laboratories", "selecting"]);
+
+        assert_eq!(FewestWords.cost(&short), 2.0);
+        assert_eq!(FewestLetters.cost(&short), 16.0);
+        assert_eq!(FewestLetters.cost(&long), 21.0);
+        assert_eq!(ShortestLongestWord.cost(&short), 9.0);
+        assert_eq!(ShortestLongestWord.cost(&long), 12.0);
+
+        let common =
+            MostCommonWords::from_ranked(["scoring", "abilities", "selecting"].map(String::from));
+        assert!(common.cost(&short) < common.cost(&long));
+    }
+
+    #[test]
+    fn objective_trait_objects() {
+        let short = path(&["abilities", "scoring"]);
+        let boxed: Box<dyn Objective> = Box::new(FewestLetters);
+        assert_eq!(boxed.cost(&short), FewestLetters.cost(&short));
+
+        // References to objectives are objectives too
+        fn cost_of<O: Objective>(objective: O, solution: &SolutionPath) -> f64 {
+            objective.cost(solution)
+        }
+        let by_ref: &dyn Objective = &FewestWords;
+        assert_eq!(cost_of(by_ref, &short), FewestWords.cost(&short));
+        assert_eq!(cost_of(boxed.as_ref(), &short), FewestLetters.cost(&short));
+    }
+}
@@ -0,0 +1,178 @@
+//! # Pangrams
+//!
+//! Find the words that solve a board on their own.
+//!
+//! A pangram covers every position of the board in a single word. Rather
+//! than leaving it to a graph search to stumble on one, every word of the
+//! lexicon long enough and using every letter of the board is traced across
+//! it with `WordTrajectories`. A board already indexed for a solve lists its
+//! pangrams from its plays instead, tracing only the words that cover it.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::pangrams::pangrams;
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! for pangram in pangrams("rvheaipnwgmo", &lexicon) {
+//!     println!("{} {:?}", pangram.word(), pangram.trajectory());
+//! }
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    index::BoardIndex,
+    lexicon::Lexicon,
+    topology::BoardTopology,
+    words::{WordTrajectories, WILDCARD},
+};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+
+/// A word covering the whole board
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Pangram {
+    word: String,
+    /// The board positions the word is traced through, in order.
+    trajectory: Vec<usize>,
+}
+
+impl Pangram {
+    /// Returns the word.
+    pub fn word(&self) -> &String {
+        &self.word
+    }
+
+    /// Returns the board positions the word is traced through.
+    pub fn trajectory(&self) -> &Vec<usize> {
+        &self.trajectory
+    }
+}
+
+/// Lists every word of `lexicon` that covers the board of `letters` on its
+/// own.
+pub fn pangrams(letters: &str, lexicon: &Lexicon) -> Vec<Pangram> {
+    let topology = BoardTopology::square(letters.chars().count());
+    let tiles: Vec<String> = letters.chars().map(String::from).collect();
+    pangrams_tiles(&tiles, &topology, lexicon)
+}
+
+/// Lists every word of `lexicon` that covers a board of tiles (each of which
+/// may hold several letters) laid out on a board of the given shape, on its
+/// own.
+///
+/// Pangrams are listed in lexicon order, each with the trajectory playing
+/// the fewest wildcards.
+pub fn pangrams_tiles(
+    tiles: &[String],
+    topology: &BoardTopology,
+    lexicon: &Lexicon,
+) -> Vec<Pangram> {
+    // A pangram plays every letter of the board at least once
+    let required: HashSet<char> = tiles
+        .iter()
+        .filter(|t| !t.starts_with(WILDCARD))
+        .flat_map(|t| t.chars())
+        .collect();
+    let min_len: usize = tiles.iter().map(|t| t.chars().count()).sum();
+
+    // A wildcard can start a word with any letter
+    let mut first_letters: Vec<char> = if tiles.iter().any(|t| t.starts_with(WILDCARD)) {
+        ('a'..='z').collect()
+    } else {
+        let first: HashSet<char> = tiles.iter().filter_map(|t| t.chars().next()).collect();
+        first.into_iter().collect()
+    };
+    first_letters.sort();
+
+    let mut pangrams = vec![];
+    for c in first_letters {
+        for word in lexicon.words_starting_with(c) {
+            if word.chars().count() < min_len || !required.iter().all(|l| word.contains(*l)) {
+                continue;
+            }
+            pangrams.extend(covering(word, tiles, topology));
+        }
+    }
+    pangrams
+}
+
+/// Lists every word playable on an indexed board (so under any word
+/// constraints the index was built with) that covers it on its own.
+///
+/// Pangrams are listed in alphabetical order, each with the trajectory
+/// playing the fewest wildcards.
+pub fn pangrams_indexed<S: CoverageState>(index: &BoardIndex<S>) -> Vec<Pangram> {
+    let words: BTreeSet<&str> = (0..index.size())
+        .flat_map(|start| index.plays_from(start))
+        .filter(|play| (0..index.size()).all(|i| play.mask.contains(i)))
+        .map(|play| play.word)
+        .collect();
+    words
+        .into_iter()
+        .filter_map(|word| covering(word, index.tiles(), index.topology()))
+        .collect()
+}
+
+/// Traces `word` across the board, returning it as a pangram along the
+/// trajectory covering every tile with the fewest wildcards, if any does
+fn covering(word: &str, tiles: &[String], topology: &BoardTopology) -> Option<Pangram> {
+    let trajectory = WordTrajectories::with_tiles(word, tiles, topology)
+        .filter(|t| t.indices().iter().collect::<HashSet<_>>().len() == tiles.len())
+        .min_by_key(|t| t.substitutions().len())?;
+    Some(Pangram {
+        word: word.to_string(),
+        trajectory: trajectory.indices().clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pangrams, pangrams_indexed};
+    use crate::solver::{
+        lexicon::{Lexicon, LEXICON_PATH},
+        solutions::SolveParams,
+        verify::verify,
+    };
+
+    #[test]
+    fn find_pangrams() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let letters = "cmlopietrnay";
+        let found = pangrams(letters, &lexicon);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].word(), "complimentary");
+        assert_eq!(
+            found[0].trajectory(),
+            &vec![0, 3, 1, 4, 2, 5, 1, 6, 9, 7, 10, 8, 11]
+        );
+        assert!(verify(letters, &[found[0].word()], &lexicon).is_ok());
+
+        // Wildcards can stand in for letters of a pangram
+        assert_eq!(pangrams("cmlopietrna?", &lexicon), found);
+
+        // March 12 '24 takes at least two words
+        assert!(pangrams("rvheaipnwgmo", &lexicon).is_empty());
+    }
+
+    #[test]
+    fn indexed_pangrams() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // An indexed board lists the same pangrams as a lexicon scan
+        for letters in ["cmlopietrnay", "cmlopietrna?", "rvheaipnwgmo"] {
+            let index = SolveParams::new(letters)
+                .unwrap()
+                .index::<u64>(&lexicon)
+                .unwrap();
+            assert_eq!(pangrams_indexed(&index), pangrams(letters, &lexicon));
+        }
+
+        // Words left out of the index are never pangrams
+        let mut params = SolveParams::new("cmlopietrnay").unwrap();
+        params.max_word_len = Some(12);
+        let index = params.index::<u64>(&lexicon).unwrap();
+        assert!(pangrams_indexed(&index).is_empty());
+    }
+}
@@ -0,0 +1,311 @@
+//! # Parallel
+//!
+//! A multi-threaded breadth-first search strategy, available with the
+//! `parallel` cargo feature (off by default, so the crate builds for WASM and
+//! other single-threaded targets).
+//!
+//! Like `Bfs`, the search expands one word at a time, so the path found has
+//! the fewest words. The nodes of each level are expanded in batches across a
+//! thread pool, with the graph acting as a shared visited set: workers only
+//! read it, and their successors are merged back into it between levels. Pools
+//! are built once per thread count and shared by every search asking for it.
+//!
+//! Workers stop expanding as soon as any of them covers every letter, so which
+//! of several equally short paths is returned can change from run to run. A
+//! `deterministic` search expands every node of the final level and merges in
+//! frontier order instead, returning the same path as `Bfs` on every run and
+//! for any number of threads. On a board with wildcards the final level is
+//! always expanded in full, and like `Bfs` the search returns the covering
+//! path played with the fewest wildcards.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::parallel::ParallelBfs;
+//! use letter_boxed::solver::solutions::{SolveParams, Solver};
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let params = SolveParams::new("rvheaipnwgmo").unwrap();
+//!
+//! let strategy = ParallelBfs::new(Some(4)).deterministic(true);
+//! let result = Solver::try_solve_with(params, &lexicon, &strategy);
+//! println!("{:?}", result.map(|s| s.words().clone()));
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    graph::{Graph, Node, NodeID, SearchOutcome, StopReason},
+    index::BoardIndex,
+    strategies::{covered_start, Limits, SearchStrategy},
+};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Dedicated pools built so far, by thread count
+static POOLS: OnceLock<Mutex<HashMap<usize, Arc<ThreadPool>>>> = OnceLock::new();
+
+/// Returns the pool of `threads` threads, building it the first time it is
+/// asked for. Thread counts are capped to the machine's, which bounds the
+/// number of pools ever built.
+fn shared_pool(threads: usize) -> Option<Arc<ThreadPool>> {
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let threads = threads.clamp(1, available);
+    let mut pools = POOLS.get_or_init(Default::default).lock().ok()?;
+    if let Some(pool) = pools.get(&threads) {
+        return Some(pool.clone());
+    }
+    let pool = Arc::new(ThreadPoolBuilder::new().num_threads(threads).build().ok()?);
+    pools.insert(threads, pool.clone());
+    Some(pool)
+}
+
+/// Breadth-first search with each level expanded across a thread pool
+#[derive(Debug, Clone, Default)]
+pub struct ParallelBfs {
+    /// Dedicated pool the searches run on (rayon's global pool if `None`)
+    pool: Option<Arc<ThreadPool>>,
+    deterministic: bool,
+}
+
+impl ParallelBfs {
+    /// Creates a new parallel search on a shared pool of `threads` threads,
+    /// at most one per core (rayon's global pool, sized to the machine, if
+    /// not given)
+    pub fn new(threads: Option<usize>) -> ParallelBfs {
+        ParallelBfs {
+            pool: threads.and_then(shared_pool),
+            deterministic: false,
+        }
+    }
+
+    /// Sets whether the search must return the same path on every run
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Expands every node of a level in parallel, returning the successors
+    /// of each node that are not yet part of the graph, with the wildcards
+    /// played to reach them (in frontier order)
+    fn expand_level<'a, S: CoverageState>(
+        &self,
+        graph: &Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        frontier: &[NodeID<S>],
+        limits: &Limits,
+    ) -> Vec<Vec<(&'a str, Node<S>, usize)>> {
+        let max_score = index.goal();
+        // Goals played with wildcards can only be compared once every one
+        // of the level has been found
+        let stop_early = !self.deterministic && !index.has_wildcards();
+        let found = AtomicBool::new(false);
+        frontier
+            .par_iter()
+            .map(|id| {
+                if (stop_early && found.load(Ordering::Relaxed)) || limits.interrupted() {
+                    return vec![];
+                }
+                let node = graph.get_node(id).unwrap();
+                let successors: Vec<_> = index
+                    .weighted_successors(node)
+                    .into_iter()
+                    .filter(|(_, n, _)| !graph.contains(n.id()))
+                    .collect();
+                if successors.iter().any(|(_, n, _)| n.score() == max_score) {
+                    found.store(true, Ordering::Relaxed);
+                }
+                successors
+            })
+            .collect()
+    }
+
+    fn search_levels<'a, S: CoverageState>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        let max_score = index.goal();
+        if let Some(outcome) = covered_start(graph, max_score) {
+            return outcome;
+        }
+        let mut frontier: Vec<NodeID<S>> = graph.nodes().iter().map(|n| n.id().clone()).collect();
+        let mut parents: HashMap<NodeID<S>, NodeID<S>> = HashMap::new();
+        // Fewest wildcards played to reach each node of the frontier
+        let mut wildcards: HashMap<NodeID<S>, usize> =
+            frontier.iter().map(|id| (id.clone(), 0)).collect();
+        let mut expansions = 0;
+        // Whether the goals of a level can only be compared once all are found
+        let full_level = self.deterministic || index.has_wildcards();
+
+        while !frontier.is_empty() {
+            // Only expand as much of the level as the budget allows
+            let remaining = limits
+                .max_expansions
+                .map_or(usize::MAX, |max| max.saturating_sub(expansions));
+            let over_budget = frontier.len() > remaining;
+            frontier.truncate(remaining);
+            expansions += frontier.len();
+
+            let expanded = self.expand_level(graph, index, &frontier, limits);
+            let mut next_frontier = vec![];
+            let mut next_wildcards: HashMap<NodeID<S>, usize> = HashMap::new();
+            let mut goals = vec![];
+            // Merged in frontier order, each node keeping the parent reaching
+            // it with the fewest wildcards (the first, on a board without any)
+            for (id, successors) in frontier.iter().zip(expanded) {
+                let new_nodes: HashSet<NodeID<S>> = graph
+                    .add_successors(
+                        id,
+                        successors.iter().map(|(w, n, _)| (*w, n.clone())).collect(),
+                    )
+                    .into_iter()
+                    .map(|n| n.id().clone())
+                    .collect();
+                for (_, n, played) in successors {
+                    let reached = wildcards[id] + played;
+                    match next_wildcards.get_mut(n.id()) {
+                        Some(fewest) if reached < *fewest => {
+                            *fewest = reached;
+                            parents.insert(n.id().clone(), id.clone());
+                        }
+                        Some(_) => (),
+                        None if new_nodes.contains(n.id()) => {
+                            next_wildcards.insert(n.id().clone(), reached);
+                            parents.insert(n.id().clone(), id.clone());
+                            if n.score() == max_score {
+                                goals.push(n.id().clone());
+                            }
+                            next_frontier.push(n.id().clone());
+                        }
+                        None => (),
+                    }
+                }
+            }
+            // A level that had to be expanded in full but was interrupted may
+            // be missing the goal that should have been returned
+            if let Some(reason) = limits.interruption().filter(|_| full_level) {
+                return SearchOutcome::Budget(reason);
+            }
+            if let Some(goal) = goals.iter().min_by_key(|id| next_wildcards[*id]) {
+                return SearchOutcome::Found(Graph::trace_path(&parents, goal));
+            }
+            // A level cut short can't be followed by the next one
+            let reason = limits
+                .interruption()
+                .or(over_budget.then_some(StopReason::Expansions));
+            if let Some(reason) = reason {
+                return SearchOutcome::Budget(reason);
+            }
+            frontier = next_frontier;
+            wildcards = next_wildcards;
+        }
+        SearchOutcome::Exhausted
+    }
+}
+
+impl<S: CoverageState> SearchStrategy<S> for ParallelBfs {
+    fn search<'a>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        // Run on the dedicated pool when a thread count was given
+        match &self.pool {
+            Some(pool) => pool.install(|| self.search_levels(graph, index, limits)),
+            None => self.search_levels(graph, index, limits),
+        }
+    }
+
+    fn is_optimal(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParallelBfs;
+    use crate::solver::{
+        graph::{Graph, SearchOutcome, StopReason},
+        index::BoardIndex,
+        lexicon::{Lexicon, LEXICON_PATH},
+        solutions::{SolveParams, Solver},
+        strategies::{Bfs, CancelToken, Limits, SearchStrategy},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn parallel_search() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        // Bfs breaks ties by the wildcards played on the last board
+        for letters in ["rvheaipnwgmo", "caitnsolgbre", "rvheaipn?gmo"] {
+            let bfs =
+                Solver::try_solve_with(SolveParams::new(letters).unwrap(), &lexicon, &Bfs).unwrap();
+
+            // Any parallel search finds a path with the fewest words
+            let strategy = ParallelBfs::new(Some(4));
+            let params = SolveParams::new(letters).unwrap();
+            let solution = Solver::try_solve_with(params, &lexicon, &strategy).unwrap();
+            assert_eq!(solution.words().len(), bfs.words().len());
+            // (the same one, when wildcards have to be compared)
+            if letters.contains('?') {
+                assert_eq!(solution.words(), bfs.words());
+            }
+
+            // ... and a deterministic one finds the same path as Bfs
+            for threads in [None, Some(1), Some(3)] {
+                let strategy = ParallelBfs::new(threads).deterministic(true);
+                let params = SolveParams::new(letters).unwrap();
+                let solution = Solver::try_solve_with(params, &lexicon, &strategy).unwrap();
+                assert_eq!(solution.words(), bfs.words());
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_shared_pool() {
+        // Searches on the same number of threads share a single pool
+        let (a, b) = (ParallelBfs::new(Some(1)), ParallelBfs::new(Some(1)));
+        assert!(Arc::ptr_eq(
+            a.pool.as_ref().unwrap(),
+            b.pool.as_ref().unwrap()
+        ));
+        assert!(ParallelBfs::new(None).pool.is_none());
+    }
+
+    #[test]
+    fn parallel_budget() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let index = BoardIndex::new(letters, &lexicon);
+        let mut graph = Graph::from_letters(letters);
+        let outcome = ParallelBfs::new(Some(2)).search(&mut graph, &index, &Limits::expansions(1));
+        assert_eq!(outcome, SearchOutcome::Budget(StopReason::Expansions));
+    }
+
+    #[test]
+    fn parallel_interrupted() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let limits = Limits {
+            cancel: Some(cancel),
+            ..Limits::default()
+        };
+
+        // A search that must expand whole levels never returns a goal from
+        // one that was interrupted
+        for (letters, deterministic) in [("rvheaipnwgmo", true), ("rvheaipn?gmo", false)] {
+            let index = BoardIndex::new(letters, &lexicon);
+            let mut graph = Graph::from_letters(letters);
+            let strategy = ParallelBfs::new(Some(2)).deterministic(deterministic);
+            let outcome = strategy.search(&mut graph, &index, &limits);
+            assert_eq!(outcome, SearchOutcome::Budget(StopReason::Cancelled));
+        }
+    }
+}
@@ -10,6 +10,7 @@
 //!     // Define input parameters
 //!     let params = SolveParams {
 //!         letters: String::from("abcdefghijklmnop"),
+//!         ..Default::default()
 //!     };
 //!
 //!     // Load a lexicon
@@ -21,11 +22,27 @@
 //!     println!("{:?}", result);
 //! }
 //! ```
+#[cfg(feature = "parallel")]
+use super::parallel::ParallelBfs;
 use super::{
-    graph::{Graph, Node},
+    coverage::{singleton, CoverageState},
+    diagnose::{diagnose_on, Diagnosis},
+    enumerate::{AnyEnumeration, Enumeration},
+    graph::{AnyGraph, Graph, Node, NodeID, SearchOutcome, StopReason},
+    index::BoardIndex,
     lexicon::Lexicon,
+    objectives::Objective,
+    pangrams::{pangrams_indexed, Pangram},
+    strategies::{
+        AStar, AnyStrategy, Beam, BestFirst, CancelToken, Limits, SearchStrategy, StrategyKind,
+    },
+    topology::{AdjacencyPredicate, AdjacencyRule, BoardTopology},
+    verify::verify_tiles,
+    words::{can_make_word_on, can_make_word_tiles, WILDCARD},
 };
-use serde::{Deserialize, Serialize};
+use num::BigUint;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BinaryHeap;
 use std::time::Instant;
 use std::{fmt, time::Duration};
 
@@ -33,37 +50,575 @@ use std::{fmt, time::Duration};
 #[derive(Debug, Serialize)]
 pub enum ParamsError {
     GameSize(String),
+    Strategy(String),
+    Played(String),
+    Start(String),
+    Adjacency(String),
+    Tiles(String),
+    Constraints(String),
 }
 
 impl fmt::Display for ParamsError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::GameSize(msg) => write!(f, "{}", msg),
+            Self::Strategy(msg) => write!(f, "{}", msg),
+            Self::Played(msg) => write!(f, "{}", msg),
+            Self::Start(msg) => write!(f, "{}", msg),
+            Self::Adjacency(msg) => write!(f, "{}", msg),
+            Self::Tiles(msg) => write!(f, "{}", msg),
+            Self::Constraints(msg) => write!(f, "{}", msg),
             // ...
         }
     }
 }
 
+/// How the solver should search for a solution
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SolveMode {
+    /// Returns the first solution reached (best-first search by default)
+    #[default]
+    Greedy,
+    /// Returns a solution with the fewest words (breadth-first search by default)
+    MinWords,
+    /// Returns a perfect solution, playing every letter exactly once besides
+    /// those shared where words chain (breadth-first search by default, for
+    /// the fewest words)
+    Perfect,
+}
+
 /// Parameters for solving Letter Boxed
 #[derive(Debug, Deserialize, Default)]
 pub struct SolveParams {
     pub letters: String,
+    /// The tile at each position, for boards with tiles of several letters
+    /// such as "qu" (comma separated in queries); together they must spell
+    /// `letters`. One letter per position if not given
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub tiles: Vec<String>,
+    /// Letters of each side of the board, in order (comma separated in
+    /// queries); together they must spell `letters`. Four sides of equal
+    /// length if not given
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub sides: Vec<String>,
+    /// Which positions may not be played one after the other
+    #[serde(default)]
+    pub adjacency: AdjacencyRule,
+    /// Decides which positions may not be played one after the other,
+    /// replacing `adjacency`
+    #[serde(skip)]
+    pub forbids: Option<AdjacencyPredicate>,
+    /// Further pairs of positions that may not be played one after the
+    /// other, such as `0-6` (comma separated in queries)
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub forbidden: Vec<String>,
+    /// Search mode used by the solver
+    #[serde(default)]
+    pub mode: SolveMode,
+    /// Maximum number of nodes to expand before giving up
+    #[serde(default)]
+    pub max_expansions: Option<usize>,
+    /// Maximum time to spend solving before giving up, in milliseconds
+    #[serde(default)]
+    pub max_duration: Option<u64>,
+    /// Token for cancelling the solve from another thread
+    #[serde(skip)]
+    pub cancel: Option<CancelToken>,
+    /// Search strategy used by the solver (chosen from `mode` if not given)
+    #[serde(default)]
+    pub strategy: Option<StrategyKind>,
+    /// Number of nodes kept per level by `StrategyKind::Beam`
+    #[serde(default)]
+    pub beam_width: Option<usize>,
+    /// Random seed used by `StrategyKind::Annealing`
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Number of threads used by `StrategyKind::ParallelBfs` (every available
+    /// thread if not given)
+    #[serde(default)]
+    pub threads: Option<usize>,
+    /// Whether `StrategyKind::ParallelBfs` must return the same solution on
+    /// every run
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Words already played, in order (comma separated in queries); the
+    /// solve finishes the game from where they leave off
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub played: Vec<String>,
+    /// Position of the last letter played, to start from an explicit state
+    /// instead of `played` (requires `start_coverage`)
+    #[serde(default)]
+    pub start_index: Option<usize>,
+    /// Positions already covered, as a binary string (the format nodes are
+    /// serialized in, with position 0 as the lowest bit)
+    #[serde(default)]
+    pub start_coverage: Option<String>,
+    /// Fewest letters a word may have
+    #[serde(default)]
+    pub min_word_len: Option<usize>,
+    /// Most letters a word may have
+    #[serde(default)]
+    pub max_word_len: Option<usize>,
+    /// Words that may not be played (comma separated in queries)
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub banned: Vec<String>,
+    /// Words the solution must play, unless already played (comma separated
+    /// in queries)
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub required: Vec<String>,
+    /// Most words the game may take, counting those already played
+    /// (requires an optimal strategy, breadth-first search by default)
+    #[serde(default)]
+    pub max_game_words: Option<usize>,
+}
+
+/// Deserializes a comma separated list of words
+pub fn comma_separated<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    let words = String::deserialize(deserializer)?;
+    Ok(words
+        .split(',')
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect())
 }
 
 impl SolveParams {
     pub fn new(letters: &str) -> Result<Self, ParamsError> {
         Ok(SolveParams {
             letters: letters.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Creates parameters for a board described by the letters of each side
+    pub fn from_sides<S: AsRef<str>>(sides: &[S]) -> Result<Self, ParamsError> {
+        let sides: Vec<String> = sides.iter().map(|s| s.as_ref().to_string()).collect();
+        Ok(SolveParams {
+            letters: sides.concat(),
+            sides,
+            ..Default::default()
+        })
+    }
+
+    /// Creates parameters for a board described by the tile at each position
+    pub fn from_tiles<S: AsRef<str>>(tiles: &[S]) -> Result<Self, ParamsError> {
+        let tiles: Vec<String> = tiles.iter().map(|t| t.as_ref().to_string()).collect();
+        Ok(SolveParams {
+            letters: tiles.concat(),
+            tiles,
+            ..Default::default()
         })
     }
+
     pub fn validate(&self) -> Result<(), ParamsError> {
-        if self.letters.len() % 4 != 0 {
+        if !self.tiles.is_empty() && self.tiles.concat() != self.letters {
+            return Err(ParamsError::Tiles(
+                "The tiles must spell out the letters of the board".to_string(),
+            ));
+        }
+        if self.tiles.iter().any(|t| t.is_empty()) {
+            return Err(ParamsError::Tiles("Tiles can't be empty".to_string()));
+        }
+        if self
+            .tiles
+            .iter()
+            .any(|t| t.contains(WILDCARD) && t.len() > 1)
+        {
+            return Err(ParamsError::Tiles(
+                "A wildcard must be a tile of its own".to_string(),
+            ));
+        }
+        if self.sides.is_empty() && self.size() % 4 != 0 {
             return Err(ParamsError::GameSize(
                 "Game size must be a multiple of 4".to_string(),
             ));
         }
+        self.topology()?;
+        if self.mode == SolveMode::MinWords && !self.strategy_kind().is_optimal() {
+            return Err(ParamsError::Strategy(
+                "Minimum word solves require an optimal strategy (bfs or a_star)".to_string(),
+            ));
+        }
+        if self.max_game_words.is_some() && !self.strategy_kind().is_optimal() {
+            return Err(ParamsError::Strategy(
+                "A maximum number of words requires an optimal strategy (bfs or a_star)"
+                    .to_string(),
+            ));
+        }
+        self.validate_constraints()?;
+        self.start_nodes::<BigUint>()?;
         Ok(())
     }
+
+    /// Checks the word constraints don't contradict each other or the words
+    /// already played
+    fn validate_constraints(&self) -> Result<(), ParamsError> {
+        let constraint = |msg: String| Err(ParamsError::Constraints(msg));
+        if let (Some(min), Some(max)) = (self.min_word_len, self.max_word_len) {
+            if min > max {
+                return constraint(format!(
+                    "Words can't be at least {} and at most {} letters long",
+                    min, max
+                ));
+            }
+        }
+        for word in self.required.iter().chain(&self.played) {
+            if !self.allows(word) {
+                return constraint(format!("{} is banned or of a length not allowed", word));
+            }
+        }
+        if let Some(max_words) = self.max_game_words {
+            let mut words = self.remaining_required();
+            words.extend(self.played.iter().map(String::as_str));
+            if max_words == 0 || words.len() > max_words {
+                return constraint(format!(
+                    "The game can't take at most {} words with {} played or required",
+                    max_words,
+                    words.len()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether the word constraints allow `word` to be played
+    pub fn allows(&self, word: &str) -> bool {
+        let len = word.chars().count();
+        self.min_word_len.is_none_or(|min| len >= min)
+            && self.max_word_len.is_none_or(|max| len <= max)
+            && !self.banned.iter().any(|banned| banned == word)
+    }
+
+    /// Returns whether any word constraint is set
+    pub fn constrained(&self) -> bool {
+        self.min_word_len.is_some()
+            || self.max_word_len.is_some()
+            || !self.banned.is_empty()
+            || !self.required.is_empty()
+            || self.max_game_words.is_some()
+    }
+
+    /// Checks every required word still to play is in `lexicon` and can be
+    /// traced on the board
+    pub fn validate_words(&self, lexicon: &Lexicon) -> Result<(), ParamsError> {
+        let tiles = self.tiles();
+        let topology = self.topology()?;
+        for word in self.remaining_required() {
+            if !lexicon.contains(word) {
+                return Err(ParamsError::Constraints(format!(
+                    "{} is not in the lexicon",
+                    word
+                )));
+            }
+            if !can_make_word_tiles(word, &tiles, &topology).any(|t| t.len() > 0) {
+                return Err(ParamsError::Constraints(format!(
+                    "{} can't be played on this board",
+                    word
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the distinct required words not played yet, in order
+    pub fn remaining_required(&self) -> Vec<&str> {
+        let mut remaining: Vec<&str> = vec![];
+        for word in &self.required {
+            if !self.played.contains(word) && !remaining.contains(&word.as_str()) {
+                remaining.push(word);
+            }
+        }
+        remaining
+    }
+
+    /// Returns the number of coverage bits a search needs: one per position
+    /// and one per required word still to play
+    pub fn coverage_size(&self) -> usize {
+        self.size() + self.remaining_required().len()
+    }
+
+    /// Returns the tile at each position: the tiles given, or else each
+    /// letter
+    pub fn tiles(&self) -> Vec<String> {
+        if self.tiles.is_empty() {
+            self.letters.chars().map(String::from).collect()
+        } else {
+            self.tiles.clone()
+        }
+    }
+
+    /// Returns the number of positions on the board
+    pub fn size(&self) -> usize {
+        if self.tiles.is_empty() {
+            self.letters.chars().count()
+        } else {
+            self.tiles.len()
+        }
+    }
+
+    /// Returns the shape of the board (the sides given, or four sides of
+    /// equal length) along with the moves forbidden on it
+    pub fn topology(&self) -> Result<BoardTopology, ParamsError> {
+        let mut topology = if self.sides.is_empty() {
+            BoardTopology::square(self.size())
+        } else if self.sides.concat() != self.letters {
+            return Err(ParamsError::GameSize(
+                "The sides must spell out the letters of the board".to_string(),
+            ));
+        } else {
+            BoardTopology::from_lengths(self.side_lengths()?)
+                .map_err(|err| ParamsError::GameSize(err.to_string()))?
+        };
+
+        topology = match &self.forbids {
+            Some(predicate) => topology.with_predicate(|a, b| predicate.forbids(a, b)),
+            None => topology.with_rule(self.adjacency),
+        };
+        for pair in &self.forbidden {
+            let positions = pair
+                .split_once('-')
+                .and_then(|(a, b)| Some((a.trim().parse().ok()?, b.trim().parse().ok()?)));
+            let Some((a, b)) = positions else {
+                return Err(ParamsError::Adjacency(format!(
+                    "Invalid forbidden pair {}",
+                    pair
+                )));
+            };
+            topology = topology
+                .forbid(a, b)
+                .map_err(|err| ParamsError::Adjacency(err.to_string()))?;
+        }
+        Ok(topology)
+    }
+
+    /// Returns the number of tiles on each side, which must be made up of
+    /// whole tiles
+    fn side_lengths(&self) -> Result<Vec<usize>, ParamsError> {
+        let tiles = self.tiles();
+        let mut tiles = tiles.iter();
+        let mut lengths = vec![];
+        for side in &self.sides {
+            let mut spelled = String::new();
+            let mut length = 0;
+            while spelled.len() < side.len() {
+                let Some(tile) = tiles.next() else { break };
+                spelled.push_str(tile);
+                length += 1;
+            }
+            if spelled != *side {
+                return Err(ParamsError::GameSize(format!(
+                    "Side {} splits a tile",
+                    side
+                )));
+            }
+            lengths.push(length);
+        }
+        Ok(lengths)
+    }
+
+    /// Builds the index of every word in `lexicon` playable on the board
+    /// under the word constraints, limited to exact covers for perfect solves
+    pub fn index<'a, S: CoverageState>(
+        &self,
+        lexicon: &'a Lexicon,
+    ) -> Result<BoardIndex<'a, S>, ParamsError> {
+        let topology = self.topology()?;
+        let index = if self.tiles.is_empty() {
+            BoardIndex::with_topology(&self.letters, topology, lexicon)
+        } else {
+            BoardIndex::with_tiles(self.tiles.clone(), topology, lexicon)
+        };
+        let index = index
+            .retain_words(|word| self.allows(word))
+            .require_words(&self.remaining_required());
+        match self.mode {
+            SolveMode::Perfect => Ok(index.exact_cover()),
+            _ => Ok(index),
+        }
+    }
+
+    /// Returns the nodes the search starts from: one per letter for a new
+    /// game, the explicit start state if given, or else every state the
+    /// played words can leave the game in (several if they can be traced
+    /// in more than one way)
+    pub fn start_nodes<S: CoverageState>(&self) -> Result<Vec<Node<S>>, ParamsError> {
+        let size = self.size();
+        match (self.start_index, &self.start_coverage) {
+            (None, None) if self.played.is_empty() => {
+                Ok((0..size).map(|i| Node::new(i, singleton(i))).collect())
+            }
+            (None, None) => self.played_nodes(),
+            (Some(index), Some(coverage)) if self.played.is_empty() => {
+                let bits = BigUint::parse_bytes(coverage.as_bytes(), 2).ok_or_else(|| {
+                    ParamsError::Start(format!("Invalid start coverage {}", coverage))
+                })?;
+                if bits.bits() > size as u64 {
+                    return Err(ParamsError::Start(
+                        "Start coverage has more positions than the board".to_string(),
+                    ));
+                }
+                if !bits.bit(index as u64) {
+                    return Err(ParamsError::Start(
+                        "Start coverage must include the start index".to_string(),
+                    ));
+                }
+                let mut state = S::empty();
+                (0..size)
+                    .filter(|i| bits.bit(*i as u64))
+                    .for_each(|i| state.set(i));
+                Ok(vec![Node::new(index, state)])
+            }
+            _ => Err(ParamsError::Start(
+                "Start from either played words, or both a start index and coverage".to_string(),
+            )),
+        }
+    }
+
+    /// Plays the played words in order, returning every state they can leave
+    /// the game in
+    fn played_nodes<S: CoverageState>(&self) -> Result<Vec<Node<S>>, ParamsError> {
+        let letters = self.letters.as_str();
+        let tiles = self.tiles();
+        let topology = self.topology()?;
+        let mut nodes: Vec<Node<S>> = vec![];
+        for (i, word) in self.played.iter().enumerate() {
+            let mut next: Vec<Node<S>> = vec![];
+            let mut traced = false;
+            let trajectories = if self.tiles.is_empty() {
+                can_make_word_on(word, letters, &topology)
+            } else {
+                can_make_word_tiles(word, &tiles, &topology)
+            };
+            for trajectory in trajectories.filter(|t| t.len() > 0) {
+                traced = true;
+                let start = trajectory.indices()[0];
+                let end = trajectory.last().unwrap();
+                let mut mask = S::empty();
+                trajectory.indices().iter().for_each(|j| mask.set(*j));
+
+                // Each word must start on the letter the previous one ended on
+                let states: Vec<S> = if i == 0 {
+                    vec![S::empty()]
+                } else {
+                    nodes
+                        .iter()
+                        .filter(|n| n.index() == start)
+                        .map(|n| n.state().clone())
+                        .collect()
+                };
+                for state in states {
+                    let node = Node::new(end, state.union(&mask));
+                    if !next.contains(&node) {
+                        next.push(node);
+                    }
+                }
+            }
+            if !traced {
+                return Err(ParamsError::Played(format!(
+                    "{} can't be played on this board",
+                    word
+                )));
+            }
+            if next.is_empty() {
+                return Err(ParamsError::Played(format!(
+                    "{} doesn't start where {} ended",
+                    word,
+                    self.played[i - 1]
+                )));
+            }
+            nodes = next;
+        }
+        Ok(nodes)
+    }
+
+    /// Returns which built-in search strategy these parameters use
+    pub fn strategy_kind(&self) -> StrategyKind {
+        self.strategy.unwrap_or(match self.mode {
+            SolveMode::Greedy if self.max_game_words.is_none() => StrategyKind::BestFirst,
+            _ => StrategyKind::Bfs,
+        })
+    }
+
+    /// Returns the search limits for these parameters, with any time limit
+    /// starting now
+    pub fn limits(&self) -> Limits {
+        Limits::new(
+            self.max_expansions,
+            self.max_duration.map(Duration::from_millis),
+        )
+        .with_cancel(self.cancel.clone())
+    }
+
+    /// Returns the search strategy for these parameters
+    pub fn strategy(&self) -> Box<dyn AnyStrategy> {
+        match self.strategy_kind() {
+            #[cfg(feature = "parallel")]
+            StrategyKind::ParallelBfs => {
+                Box::new(ParallelBfs::new(self.threads).deterministic(self.deterministic))
+            }
+            kind => kind.build(
+                self.beam_width.unwrap_or(DEFAULT_BEAM_WIDTH),
+                self.seed.unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Number of nodes kept per level when beam searching, unless specified
+pub const DEFAULT_BEAM_WIDTH: usize = 32;
+
+/// Number of nodes a failed solve's diagnosis may expand, unless the solve
+/// itself was limited
+const DIAGNOSIS_MAX_EXPANSIONS: usize = 1_000_000;
+
+/// Widths of the beam searches refining an anytime solve, in order
+const ANYTIME_BEAM_WIDTHS: [usize; 3] = [4, 16, 64];
+
+/// Whether a solution is known to use the fewest possible words
+///
+/// Searches only play words that cover a new letter, so a proof is over
+/// those chains: a chain that spends a word covering nothing, just to move
+/// to a letter it can continue from, is never considered.
+///
+/// Ordered from least to most certain.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Optimality {
+    /// The search makes no guarantee on the number of words.
+    UNPROVEN,
+    /// The search proved no solution with fewer words exists among the
+    /// chains whose every word covers a new letter.
+    PROVEN,
+}
+
+/// A wildcard played in a solution, and the letter it stood for
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    /// The word the wildcard was played in.
+    word: String,
+    /// The position of the wildcard on the board.
+    position: usize,
+    /// The letter it stood for.
+    letter: char,
+}
+
+impl Substitution {
+    /// Returns the word the wildcard was played in.
+    pub fn word(&self) -> &String {
+        &self.word
+    }
+
+    /// Returns the position of the wildcard on the board.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the letter the wildcard stood for.
+    pub fn letter(&self) -> char {
+        self.letter
+    }
 }
 
 /// Represents a solution to Letter Boxed
@@ -72,44 +627,564 @@ pub struct Solution<'a> {
     /// List of words forming the solution.
     words: Vec<String>,
     /// Graph representing all explored nodes and edges
-    graph: Graph<'a>,
+    graph: AnyGraph<'a>,
+    /// States representing the nodes in the solution path.
+    states: Vec<Node>,
+    /// Whether the number of words is proven minimal.
+    optimality: Optimality,
+}
+
+impl<'a> Solution<'a> {
+    /// Returns the words forming the solution.
+    pub fn words(&self) -> &Vec<String> {
+        &self.words
+    }
+
+    /// Returns the states of the nodes in the solution path.
+    pub fn states(&self) -> &Vec<Node> {
+        &self.states
+    }
+
+    /// Returns whether the number of words is proven minimal.
+    pub fn optimality(&self) -> Optimality {
+        self.optimality
+    }
+
+    /// Returns the words and states of the solution, without its graph.
+    pub fn path(&self) -> SolutionPath {
+        SolutionPath::new(self.words.clone(), self.states.clone())
+    }
+
+    /// Returns the letters not covered by the last state of the path, in
+    /// board order (none for a complete solution).
+    pub fn uncovered(&self, letters: &str) -> Vec<char> {
+        let Some(last) = self.states.last() else {
+            return letters.chars().collect();
+        };
+        letters
+            .chars()
+            .enumerate()
+            .filter(|(i, _)| !last.state().contains(*i))
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    /// Returns the tiles not covered by the last state of the path, in board
+    /// order, for boards with tiles of several letters.
+    pub fn uncovered_tiles(&self, tiles: &[String]) -> Vec<String> {
+        tiles
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.states.last().is_none_or(|n| !n.state().contains(*i)))
+            .map(|(_, tile)| tile.clone())
+            .collect()
+    }
+
+    /// Returns the letter each wildcard played stood for, in the order
+    /// played (none on a board without wildcards).
+    pub fn substitutions(&self, tiles: &[String], topology: &BoardTopology) -> Vec<Substitution> {
+        self.path().substitutions(tiles, topology)
+    }
+}
+
+/// The outcome of a solve that may stop early
+#[derive(Debug)]
+pub enum SolveOutcome<'a> {
+    /// A path covering every letter was found.
+    Solved(Solution<'a>),
+    /// A limit was hit first, leaving the path reaching the highest coverage.
+    Partial(SolutionError, Solution<'a>),
+    /// No solution was found.
+    Failed(SolutionError),
+}
+
+impl<'a> SolveOutcome<'a> {
+    /// Returns the solution, or why there is none (dropping any partial path)
+    pub fn into_result(self) -> Result<Solution<'a>, SolutionError> {
+        match self {
+            Self::Solved(solution) => Ok(solution),
+            Self::Partial(err, _) | Self::Failed(err) => Err(err),
+        }
+    }
+}
+
+/// A solution to Letter Boxed without the graph explored to find it
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SolutionPath {
+    /// List of words forming the solution.
+    words: Vec<String>,
     /// States representing the nodes in the solution path.
     states: Vec<Node>,
 }
 
+impl SolutionPath {
+    /// Creates a new solution path from its words and the states between them.
+    pub fn new(words: Vec<String>, states: Vec<Node>) -> SolutionPath {
+        SolutionPath { words, states }
+    }
+
+    /// Returns the words forming the solution.
+    pub fn words(&self) -> &Vec<String> {
+        &self.words
+    }
+
+    /// Returns the states of the nodes in the solution path.
+    pub fn states(&self) -> &Vec<Node> {
+        &self.states
+    }
+
+    /// Returns how many letters the words play more than once, beyond those
+    /// shared where they chain: zero for a perfect solution.
+    ///
+    /// Counted for new games on boards of single letters, as the letters of
+    /// the words less the joins and the positions covered.
+    pub fn reuse(&self) -> usize {
+        let letters: usize = self.words.iter().map(|w| w.chars().count()).sum();
+        let joins = self.words.len().saturating_sub(1);
+        let covered = self.states.last().map_or(0, |n| n.score());
+        letters.saturating_sub(joins + covered)
+    }
+
+    /// Returns the letter each wildcard played stood for, in the order
+    /// played (none on a board without wildcards).
+    ///
+    /// Each word is traced between the positions its states start and end
+    /// on, over exactly the positions it covers, with as few wildcards as
+    /// possible.
+    pub fn substitutions(&self, tiles: &[String], topology: &BoardTopology) -> Vec<Substitution> {
+        if !tiles.iter().any(|t| t == &WILDCARD.to_string()) {
+            return vec![];
+        }
+        let mut substitutions = vec![];
+        for (word, pair) in self.words.iter().zip(self.states.windows(2)) {
+            let (before, after) = (&pair[0], &pair[1]);
+            let traced = can_make_word_tiles(word, tiles, topology)
+                .filter(|t| t.indices()[0] == before.index() && t.last() == Some(after.index()))
+                .filter(|t| {
+                    let mut state = before.state().clone();
+                    t.indices().iter().for_each(|i| state.set(*i));
+                    state == *after.state()
+                })
+                .min_by_key(|t| t.substitutions().len());
+            if let Some(trajectory) = traced {
+                substitutions.extend(trajectory.substitutions().iter().map(
+                    |(position, letter)| Substitution {
+                        word: word.clone(),
+                        position: *position,
+                        letter: *letter,
+                    },
+                ));
+            }
+        }
+        substitutions
+    }
+}
+
+/// A solution along with its cost under some `Objective`
+#[derive(Serialize, Debug, Clone)]
+pub struct RankedSolution {
+    /// Cost of the solution (lower is better)
+    cost: f64,
+    solution: SolutionPath,
+    /// Position of the solution in enumeration order, used to break ties
+    #[serde(skip)]
+    order: usize,
+}
+
+impl RankedSolution {
+    /// Returns the cost of the solution under the objective it was ranked by.
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+
+    /// Returns the ranked solution.
+    pub fn solution(&self) -> &SolutionPath {
+        &self.solution
+    }
+}
+
+impl PartialEq for RankedSolution {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for RankedSolution {}
+
+impl Ord for RankedSolution {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.cost
+            .total_cmp(&other.cost)
+            .then(self.order.cmp(&other.order))
+    }
+}
+
+impl PartialOrd for RankedSolution {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// A solver for Letter Boxed
 pub struct Solver {}
 
 impl<'a> Solver {
     /// Solves the game based on the given letters and lexicon, returning a Solution object
     pub fn solve(params: SolveParams, lexicon: &'a Lexicon) -> Option<Solution<'a>> {
+        Self::try_solve(params, lexicon).ok()
+    }
+
+    /// Lazily enumerates every distinct solution using at most `max_words`
+    /// words, not counting any already played, or reports why the board
+    /// can't be enumerated as described
+    pub fn enumerate(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        max_words: usize,
+    ) -> Result<AnyEnumeration<'a>, ParamsError> {
+        // Store node coverage in the narrowest state that fits the board
+        let size = params.coverage_size();
+        if u64::fits(size) {
+            Self::enumerate_with::<u64>(params, lexicon, max_words).map(AnyEnumeration::U64)
+        } else if u128::fits(size) {
+            Self::enumerate_with::<u128>(params, lexicon, max_words).map(AnyEnumeration::U128)
+        } else {
+            Self::enumerate_with::<BigUint>(params, lexicon, max_words).map(AnyEnumeration::Big)
+        }
+    }
+
+    /// Enumerates solutions with node coverage stored as `S`
+    fn enumerate_with<S: CoverageState>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        max_words: usize,
+    ) -> Result<Enumeration<'a, S>, ParamsError> {
+        // A game under way is enumerated from where its played words left it
+        let starts = params.start_nodes()?;
+        Ok(Enumeration::from_index(params.index(lexicon)?, max_words)
+            .with_starts(starts)
+            .with_limits(params.limits()))
+    }
+
+    /// Returns the `k` best solutions of at most `max_words` words under the
+    /// given objective, best first. Ties are broken by enumeration order.
+    pub fn top_k<O: Objective>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        max_words: usize,
+        k: usize,
+        objective: O,
+    ) -> Result<Vec<RankedSolution>, ParamsError> {
+        // Keep the k best solutions seen so far, with the worst on top
+        let mut heap: BinaryHeap<RankedSolution> = BinaryHeap::new();
+        for (order, solution) in Self::enumerate(params, lexicon, max_words)?.enumerate() {
+            heap.push(RankedSolution {
+                cost: objective.cost(&solution),
+                solution,
+                order,
+            });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        Ok(heap.into_sorted_vec())
+    }
+
+    /// Solves the game like `solve`, but reports why no solution was returned
+    pub fn try_solve(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+    ) -> Result<Solution<'a>, SolutionError> {
+        Self::attempt(params, lexicon).into_result()
+    }
+
+    /// Solves the game with the given search strategy instead of the one
+    /// chosen by the parameters
+    pub fn try_solve_with<T: AnyStrategy + ?Sized>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        strategy: &T,
+    ) -> Result<Solution<'a>, SolutionError> {
+        Self::attempt_with(params, lexicon, strategy).into_result()
+    }
+
+    /// Solves the game on an already indexed board, so that repeated solves
+    /// of the same board skip tracing the lexicon again
+    pub fn try_solve_indexed<S: CoverageState, T: SearchStrategy<S> + ?Sized>(
+        params: &SolveParams,
+        index: &BoardIndex<'a, S>,
+        strategy: &T,
+    ) -> Result<Solution<'a>, SolutionError> {
+        Self::attempt_indexed(params, index, strategy).into_result()
+    }
+
+    /// Solves the game within the limits of the parameters, keeping the best
+    /// partial path if a limit is hit before a solution is found
+    pub fn attempt(params: SolveParams, lexicon: &'a Lexicon) -> SolveOutcome<'a> {
+        let strategy = params.strategy();
+        Self::attempt_with(params, lexicon, strategy.as_ref())
+    }
+
+    /// Attempts to solve the game with the given search strategy instead of
+    /// the one chosen by the parameters
+    pub fn attempt_with<T: AnyStrategy + ?Sized>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        strategy: &T,
+    ) -> SolveOutcome<'a> {
+        Self::attempt_listing(params, lexicon, strategy, None)
+    }
+
+    /// Attempts to solve the game like `attempt`, also listing the pangrams
+    /// among the words indexed for the solve
+    pub fn attempt_with_pangrams(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+    ) -> (SolveOutcome<'a>, Vec<Pangram>) {
+        let strategy = params.strategy();
+        let mut pangrams = vec![];
+        let outcome =
+            Self::attempt_listing(params, lexicon, strategy.as_ref(), Some(&mut pangrams));
+        (outcome, pangrams)
+    }
+
+    /// Attempts to solve the game with the given search strategy, listing
+    /// the pangrams of the board into `pangrams` if given
+    fn attempt_listing<T: AnyStrategy + ?Sized>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        strategy: &T,
+        pangrams: Option<&mut Vec<Pangram>>,
+    ) -> SolveOutcome<'a> {
+        // Store node coverage in the narrowest state that fits the board
+        let size = params.coverage_size();
+        if u64::fits(size) {
+            Self::search_with::<u64, T>(params, lexicon, strategy, pangrams)
+        } else if u128::fits(size) {
+            Self::search_with::<u128, T>(params, lexicon, strategy, pangrams)
+        } else {
+            Self::search_with::<BigUint, T>(params, lexicon, strategy, pangrams)
+        }
+    }
+
+    /// Searches for a solution with node coverage stored as `S`, listing the
+    /// pangrams of the board into `pangrams` if given
+    fn search_with<S: CoverageState, T: SearchStrategy<S> + ?Sized>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        strategy: &T,
+        pangrams: Option<&mut Vec<Pangram>>,
+    ) -> SolveOutcome<'a> {
+        // The time limit covers building the index as well as the search
+        let limits = params.limits();
+        let Ok(index) = params.index(lexicon) else {
+            return SolveOutcome::Failed(SolutionError::GENERAL);
+        };
+        if let Some(pangrams) = pangrams {
+            *pangrams = pangrams_indexed(&index);
+        }
+        let outcome = Self::search_indexed(&params, &index, strategy, &limits);
+        if let SolveOutcome::Solved(solution) = &outcome {
+            debug_verify(&params, solution, lexicon);
+        }
+        outcome
+    }
+
+    /// Attempts to solve the game on an already indexed board
+    pub fn attempt_indexed<S: CoverageState, T: SearchStrategy<S> + ?Sized>(
+        params: &SolveParams,
+        index: &BoardIndex<'a, S>,
+        strategy: &T,
+    ) -> SolveOutcome<'a> {
+        Self::search_indexed(params, index, strategy, &params.limits())
+    }
+
+    /// Searches from the start nodes of the parameters on an indexed board
+    fn search_indexed<S: CoverageState, T: SearchStrategy<S> + ?Sized>(
+        params: &SolveParams,
+        index: &BoardIndex<'a, S>,
+        strategy: &T,
+        limits: &Limits,
+    ) -> SolveOutcome<'a> {
+        let Ok(starts) = params.start_nodes() else {
+            return SolveOutcome::Failed(SolutionError::GENERAL);
+        };
         // Build graph and use it to get a path to the solution
-        let mut g = Graph::from_letters(&params.letters.as_str());
-        let node_ids = g.get_node_path(&params.letters.as_str(), lexicon)?;
+        let mut g: Graph<'a, S> = Graph::with_nodes(starts);
+        match strategy.search(&mut g, index, limits) {
+            SearchOutcome::Found(node_ids) => {
+                let optimality = if strategy.is_optimal() {
+                    Optimality::PROVEN
+                } else {
+                    Optimality::UNPROVEN
+                };
+                // A solution taking more words than allowed is none at all
+                let too_long = |s: &Solution| {
+                    params
+                        .max_game_words
+                        .is_some_and(|max| s.words.len() + params.played.len() > max)
+                };
+                match Self::collect(g, node_ids, optimality, index.size()) {
+                    Some(solution) if !too_long(&solution) => SolveOutcome::Solved(solution),
+                    _ => SolveOutcome::Failed(SolutionError::GENERAL),
+                }
+            }
+            SearchOutcome::Exhausted => SolveOutcome::Failed(SolutionError::GENERAL),
+            SearchOutcome::Budget(reason) => {
+                let reason = match reason {
+                    StopReason::Expansions => SolutionError::BUDGET,
+                    StopReason::Timeout => SolutionError::TIMEOUT,
+                    StopReason::Cancelled => SolutionError::CANCELLED,
+                };
+                let partial = g.best_partial_path().and_then(|node_ids| {
+                    Self::collect(g, node_ids, Optimality::UNPROVEN, index.size())
+                });
+                match partial {
+                    Some(partial) => SolveOutcome::Partial(reason, partial),
+                    None => SolveOutcome::Failed(reason),
+                }
+            }
+        }
+    }
+
+    /// Solves the game as an anytime search, calling `on_improvement` with
+    /// each better solution as soon as it is found: one with fewer words than
+    /// the last, or the same number of words proven minimal.
+    ///
+    /// A greedy search answers first, wider and wider beam searches refine
+    /// the answer and A* finally proves the fewest words, all on the same
+    /// board index and within the limits of the parameters (whose strategy
+    /// is ignored). Returns the best solution found.
+    pub fn solve_anytime<F: FnMut(&Solution<'a>)>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        on_improvement: F,
+    ) -> Result<Solution<'a>, SolutionError> {
+        let size = params.coverage_size();
+        if u64::fits(size) {
+            Self::anytime_with::<u64, F>(params, lexicon, on_improvement)
+        } else if u128::fits(size) {
+            Self::anytime_with::<u128, F>(params, lexicon, on_improvement)
+        } else {
+            Self::anytime_with::<BigUint, F>(params, lexicon, on_improvement)
+        }
+    }
+
+    /// Runs an anytime search with node coverage stored as `S`
+    fn anytime_with<S: CoverageState, F: FnMut(&Solution<'a>)>(
+        params: SolveParams,
+        lexicon: &'a Lexicon,
+        mut on_improvement: F,
+    ) -> Result<Solution<'a>, SolutionError> {
+        let limits = params.limits();
+        let index = params.index(lexicon).map_err(|_| SolutionError::GENERAL)?;
+
+        let mut phases: Vec<Box<dyn SearchStrategy<S>>> = vec![Box::new(BestFirst)];
+        for width in ANYTIME_BEAM_WIDTHS {
+            phases.push(Box::new(Beam::new(width)));
+        }
+        phases.push(Box::new(AStar));
 
-        // Collect results
+        let mut best: Result<Solution<'a>, SolutionError> = Err(SolutionError::GENERAL);
+        for strategy in phases {
+            match Self::search_indexed(&params, &index, strategy.as_ref(), &limits) {
+                SolveOutcome::Solved(solution) => {
+                    debug_verify(&params, &solution, lexicon);
+                    let improves = match &best {
+                        Ok(b) => {
+                            solution.words.len() < b.words.len()
+                                || (solution.words.len() == b.words.len()
+                                    && solution.optimality > b.optimality)
+                        }
+                        Err(_) => true,
+                    };
+                    if improves {
+                        on_improvement(&solution);
+                        best = Ok(solution);
+                    }
+                }
+                SolveOutcome::Partial(err, _) | SolveOutcome::Failed(err) => {
+                    if best.is_err() {
+                        best = Err(err);
+                    }
+                    // Later phases would hit the same limit straight away
+                    if limits.interrupted() {
+                        break;
+                    }
+                }
+            }
+            if best
+                .as_ref()
+                .is_ok_and(|b| b.optimality == Optimality::PROVEN)
+            {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Collects the words and states along a path of nodes into a Solution
+    /// (keeping only the coverage of the board's `size` positions)
+    fn collect<S: CoverageState>(
+        g: Graph<'a, S>,
+        node_ids: Vec<NodeID<S>>,
+        optimality: Optimality,
+        size: usize,
+    ) -> Option<Solution<'a>> {
         let mut words = vec![];
         let mut states = vec![];
         for i in 0..node_ids.len() {
             if i != node_ids.len() - 1 {
                 words.push(g.get_edge(&node_ids[i], &node_ids[i + 1])?.word.to_string());
             }
-            states.push(Node::from_id(node_ids[i].clone()));
+            // Bits of required words aren't positions on the board
+            let node = Node::from_id(node_ids[i].clone());
+            let mut state = BigUint::empty();
+            (0..size)
+                .filter(|j| node.state().contains(*j))
+                .for_each(|j| state.set(j));
+            states.push(Node::new(node.index(), state));
         }
         // Return successful solution
         Some(Solution {
             words,
-            graph: g,
+            graph: S::any_graph(g),
             states,
+            optimality,
         })
     }
 }
 
+/// Checks a solution found by the solver with the independent verifier, in
+/// debug builds only. A game in progress is checked as the words played
+/// followed by the solution, which together must solve the board; games
+/// started from an explicit state, or from played words outside the
+/// lexicon, can't be checked this way.
+fn debug_verify(params: &SolveParams, solution: &Solution, lexicon: &Lexicon) {
+    let checkable =
+        params.start_index.is_none() && params.played.iter().all(|w| lexicon.contains(w));
+    if cfg!(debug_assertions) && checkable {
+        let Ok(topology) = params.topology() else {
+            return;
+        };
+        let words: Vec<&String> = params.played.iter().chain(solution.words()).collect();
+        if let Err(err) = verify_tiles(&params.tiles(), &topology, &words, lexicon) {
+            panic!("Solver returned an invalid solution {:?}: {}", words, err);
+        }
+    }
+}
+
 /// Errors that can occur during solution generation.
 #[derive(Debug, Serialize)]
 pub enum SolutionError {
     /// General error indicating failure in solution generation.
     GENERAL,
+    /// The search spent its budget before it could find a solution.
+    BUDGET,
+    /// The search ran out of time before it could find a solution.
+    TIMEOUT,
+    /// The search was cancelled before it could find a solution.
+    CANCELLED,
 }
 
 #[derive(Debug, Serialize)]
@@ -123,6 +1198,8 @@ pub struct SolutionMeta {
 enum SolutionStatus {
     /// Indicating a successful solution.
     SUCCESS,
+    /// Indicating a limit was hit first, returning the best partial path.
+    PARTIAL(SolutionError),
     /// Indicating failure in finding a solution.
     FAIL(SolutionError),
 }
@@ -134,6 +1211,20 @@ pub struct SolutionResult<'a> {
     solution: Option<Solution<'a>>,
     /// Status of the solution attempt.
     meta: SolutionMeta,
+    /// Letters left uncovered by a partial solution.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    uncovered: Vec<String>,
+    /// Why the board couldn't be solved, if it can't be.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnosis: Option<Diagnosis>,
+    /// The letter each wildcard of the solution stood for.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    substitutions: Vec<Substitution>,
+    /// Whether a single word covers the whole board.
+    pangram: bool,
+    /// Every word covering the whole board, with its trajectory.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pangrams: Vec<Pangram>,
 }
 
 impl<'a> SolutionResult<'a> {
@@ -142,37 +1233,81 @@ impl<'a> SolutionResult<'a> {
         // Solve the puzzle and compute runtime
         // TODO: more expressive instrumentation of solve
         //       that returns an instance of SolutionMeta
+        let letters = params.letters.clone();
+        let tiles = params.tiles();
+        let new_game = params.played.is_empty() && params.start_index.is_none();
+        // The diagnosis only knows boards of single letters, words without
+        // constraints, and paths that may repeat letters
+        let single_letters = params.tiles.is_empty();
+        let constrained = params.constrained() || params.mode == SolveMode::Perfect;
+        let topology = params.topology();
+        // The diagnosis shares the solve's time limit and cancellation, with
+        // its own budget of expansions
+        let diagnosis_limits = Limits {
+            max_expansions: params.max_expansions.or(Some(DIAGNOSIS_MAX_EXPANSIONS)),
+            ..params.limits()
+        };
+        // Pangrams come from the words indexed for the solve, which the
+        // constraints have already been applied to
         let now = Instant::now();
-        let solver = Solver::solve(params, lexicon);
+        let (outcome, pangrams) = Solver::attempt_with_pangrams(params, lexicon);
         let runtime = Instant::now() - now;
 
-        if let Some(solution) = solver {
-            SolutionResult {
-                solution: Some(solution),
-                meta: SolutionMeta {
-                    status: SolutionStatus::SUCCESS,
-                    runtime: runtime,
-                },
-            }
-        } else {
+        let (solution, status) = match outcome {
+            SolveOutcome::Solved(solution) => (Some(solution), SolutionStatus::SUCCESS),
+            // Stopped early with the best partial path
+            SolveOutcome::Partial(err, partial) => (Some(partial), SolutionStatus::PARTIAL(err)),
             // Failed solve
-            SolutionResult {
-                solution: None,
-                meta: SolutionMeta {
-                    status: SolutionStatus::FAIL(SolutionError::GENERAL),
-                    runtime: runtime,
-                },
+            SolveOutcome::Failed(err) => (None, SolutionStatus::FAIL(err)),
+        };
+        let substitutions = match (&solution, &topology) {
+            (Some(solution), Ok(topology)) => solution.substitutions(&tiles, topology),
+            _ => vec![],
+        };
+        let uncovered = match (&status, &solution) {
+            (SolutionStatus::PARTIAL(_), Some(partial)) => partial.uncovered_tiles(&tiles),
+            _ => vec![],
+        };
+        // Only a new game failing outright says something about the board
+        let diagnosis = match (&status, topology) {
+            (SolutionStatus::FAIL(SolutionError::GENERAL), Ok(topology))
+                if new_game && single_letters && !constrained =>
+            {
+                Some(diagnose_on(&letters, topology, lexicon, &diagnosis_limits))
             }
+            _ => None,
+        };
+        SolutionResult {
+            solution,
+            meta: SolutionMeta { status, runtime },
+            uncovered,
+            diagnosis,
+            substitutions,
+            pangram: !pangrams.is_empty(),
+            pangrams,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Solver;
+    use super::{
+        Optimality, ParamsError, SolutionError, SolutionResult, SolutionStatus, SolveMode,
+        SolveOutcome, Solver,
+    };
+    use crate::solver::graph::AnyGraph;
+    use crate::solver::index::BoardIndex;
     use crate::solver::lexicon::{Lexicon, LEXICON_PATH};
+    use crate::solver::objectives::{
+        FewestLetters, FewestWildcards, LeastReuse, Objective, ShortestLongestWord,
+    };
     use crate::solver::solutions::SolveParams;
-    use crate::solver::words::random_string;
+    use crate::solver::strategies::{BestFirst, Bfs, CancelToken, StrategyKind};
+    use crate::solver::topology::{AdjacencyPredicate, AdjacencyRule};
+    use crate::solver::verify::{verify, verify_on, verify_tiles};
+    use crate::solver::words::{can_make_word_on, random_string};
+    use num::BigUint;
+    use serde_json::json;
 
     #[test]
     fn graph_search_solve() {
@@ -196,4 +1331,696 @@ mod tests {
         let solution = Solver::solve(params, &lexicon);
         println!("{:?}", solution);
     }
+
+    #[test]
+    fn min_words_solve() {
+        // March 12 '24
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        let greedy = Solver::solve(SolveParams::new(letters).unwrap(), &lexicon).unwrap();
+        assert_eq!(greedy.optimality(), Optimality::UNPROVEN);
+
+        let params = SolveParams {
+            mode: SolveMode::MinWords,
+            ..SolveParams::new(letters).unwrap()
+        };
+        let minimal = Solver::solve(params, &lexicon).unwrap();
+        assert_eq!(minimal.optimality(), Optimality::PROVEN);
+        assert!(minimal.words().len() <= greedy.words().len());
+        assert_eq!(minimal.states().len(), minimal.words().len() + 1);
+
+        // Consecutive words must chain on their shared letter
+        for pair in minimal.words().windows(2) {
+            assert_eq!(pair[0].chars().last(), pair[1].chars().next());
+        }
+    }
+
+    #[test]
+    fn top_k_solve() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        let ranked = Solver::top_k(
+            SolveParams::new(letters).unwrap(),
+            &lexicon,
+            2,
+            3,
+            FewestLetters,
+        )
+        .unwrap();
+        assert_eq!(ranked.len(), 3);
+        for pair in ranked.windows(2) {
+            assert!(pair[0].cost() <= pair[1].cost());
+        }
+        // Nothing left out should beat the worst kept solution
+        let worst = ranked.last().unwrap().cost();
+        for solution in Solver::enumerate(SolveParams::new(letters).unwrap(), &lexicon, 2).unwrap()
+        {
+            let cost = FewestLetters.cost(&solution);
+            assert!(cost >= worst || ranked.iter().any(|r| *r.solution() == solution));
+        }
+
+        // Objectives can be passed as trait objects
+        let objective: Box<dyn Objective> = Box::new(ShortestLongestWord);
+        let ranked = Solver::top_k(
+            SolveParams::new(letters).unwrap(),
+            &lexicon,
+            2,
+            1,
+            objective,
+        )
+        .unwrap();
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(
+            ranked[0].cost(),
+            ShortestLongestWord.cost(ranked[0].solution())
+        );
+    }
+
+    #[test]
+    fn strategy_params() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // A* proves optimality just like the default minimum word search
+        let params = SolveParams {
+            mode: SolveMode::MinWords,
+            strategy: Some(StrategyKind::AStar),
+            ..SolveParams::new(letters).unwrap()
+        };
+        assert!(params.validate().is_ok());
+        let solution = Solver::solve(params, &lexicon).unwrap();
+        assert_eq!(solution.optimality(), Optimality::PROVEN);
+        assert_eq!(solution.words().len(), 2);
+
+        // Beam search can't prove anything
+        let params = SolveParams {
+            strategy: Some(StrategyKind::Beam),
+            beam_width: Some(4),
+            ..SolveParams::new(letters).unwrap()
+        };
+        assert!(params.validate().is_ok());
+        let solution = Solver::solve(params, &lexicon).unwrap();
+        assert_eq!(solution.optimality(), Optimality::UNPROVEN);
+
+        // ... so it can't be used for minimum word solves
+        let params = SolveParams {
+            mode: SolveMode::MinWords,
+            strategy: Some(StrategyKind::Beam),
+            ..SolveParams::new(letters).unwrap()
+        };
+        assert!(matches!(params.validate(), Err(ParamsError::Strategy(_))));
+
+        // Parallel search proves it found the fewest words
+        #[cfg(feature = "parallel")]
+        {
+            let params = SolveParams {
+                mode: SolveMode::MinWords,
+                strategy: Some(StrategyKind::ParallelBfs),
+                threads: Some(2),
+                deterministic: true,
+                ..SolveParams::new(letters).unwrap()
+            };
+            assert!(params.validate().is_ok());
+            let solution = Solver::solve(params, &lexicon).unwrap();
+            assert_eq!(solution.words().len(), 2);
+            assert_eq!(solution.optimality(), Optimality::PROVEN);
+        }
+    }
+
+    #[test]
+    fn coverage_state_widths() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Every coverage state finds the same solution
+        let params = || SolveParams::new(letters).unwrap();
+        let narrow = Solver::search_with::<u64, _>(params(), &lexicon, &Bfs, None)
+            .into_result()
+            .unwrap();
+        let wide = Solver::search_with::<u128, _>(params(), &lexicon, &Bfs, None)
+            .into_result()
+            .unwrap();
+        let big = Solver::search_with::<BigUint, _>(params(), &lexicon, &Bfs, None)
+            .into_result()
+            .unwrap();
+        assert_eq!(narrow.words(), big.words());
+        assert_eq!(wide.words(), big.words());
+        assert_eq!(narrow.states(), big.states());
+        // ... and keeps its graph in the state it searched with, which
+        // serializes the same way
+        assert!(matches!(narrow.graph, AnyGraph::U64(_)));
+        assert!(matches!(big.graph, AnyGraph::Big(_)));
+        assert_eq!(json!(narrow.graph), json!(big.graph));
+    }
+
+    #[test]
+    fn indexed_solves() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let params = SolveParams::new(letters).unwrap();
+
+        // One index serves any number of solves of the same board
+        let index: BoardIndex<u64> = BoardIndex::new(letters, &lexicon);
+        let greedy = Solver::try_solve_indexed(&params, &index, &BestFirst).unwrap();
+        let fewest = Solver::try_solve_indexed(&params, &index, &Bfs).unwrap();
+        assert_eq!(fewest.words().len(), 2);
+        assert!(fewest.words().len() <= greedy.words().len());
+    }
+
+    #[test]
+    fn min_words_budget() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let params = SolveParams {
+            mode: SolveMode::MinWords,
+            max_expansions: Some(1),
+            ..SolveParams::new("rvheaipnwgmo").unwrap()
+        };
+        let result = Solver::try_solve(params, &lexicon);
+        assert!(matches!(result, Err(SolutionError::BUDGET)));
+    }
+
+    #[test]
+    fn anytime_solve() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Every solution reported improves on the one before it
+        let mut reported: Vec<(usize, Optimality)> = vec![];
+        let params = SolveParams::new(letters).unwrap();
+        let best = Solver::solve_anytime(params, &lexicon, |solution| {
+            reported.push((solution.words().len(), solution.optimality()));
+        })
+        .unwrap();
+        assert!(!reported.is_empty());
+        for pair in reported.windows(2) {
+            assert!(pair[1].0 < pair[0].0 || pair[1].1 > pair[0].1);
+        }
+
+        // ... ending with the proven fewest words
+        let fewest =
+            Solver::try_solve_with(SolveParams::new(letters).unwrap(), &lexicon, &Bfs).unwrap();
+        assert_eq!(best.optimality(), Optimality::PROVEN);
+        assert_eq!(best.words().len(), fewest.words().len());
+        assert_eq!(
+            *reported.last().unwrap(),
+            (best.words().len(), Optimality::PROVEN)
+        );
+
+        // Out of time, nothing is reported
+        let params = SolveParams {
+            max_duration: Some(0),
+            ..SolveParams::new(letters).unwrap()
+        };
+        let result = Solver::solve_anytime(params, &lexicon, |_| panic!("No solution expected"));
+        assert!(matches!(result, Err(SolutionError::TIMEOUT)));
+    }
+
+    #[test]
+    fn topology_solve() {
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Sides of different lengths, and more or fewer than four of them
+        for sides in [
+            vec!["tra", "in", "gsle", "o"],
+            vec!["rvhe", "aipn", "wgmo"],
+            vec!["rv", "he", "ai", "pn", "wg", "mo"],
+        ] {
+            let params = SolveParams::from_sides(&sides).unwrap();
+            assert!(params.validate().is_ok());
+            let topology = params.topology().unwrap();
+            let solution = Solver::try_solve(params, &lexicon).unwrap();
+            let letters = sides.concat();
+            assert!(verify_on(&letters, &topology, solution.words(), &lexicon).is_ok());
+        }
+
+        // The sides must spell out the board
+        let mut params = SolveParams::new("rvheaipnwgmo").unwrap();
+        params.sides = vec!["rvhe".to_string(), "aipn".to_string()];
+        assert!(matches!(params.validate(), Err(ParamsError::GameSize(_))));
+        // ... and aren't enumerated as some other board when they don't
+        assert!(matches!(
+            Solver::enumerate(params, &lexicon, 2),
+            Err(ParamsError::GameSize(_))
+        ));
+        let params = SolveParams::from_sides(&["rvheaipnwgmo"]).unwrap();
+        assert!(matches!(params.validate(), Err(ParamsError::GameSize(_))));
+    }
+
+    #[test]
+    fn adjacency_rule_solve() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Playing only between opposite sides never links the two pairs
+        let mut params = SolveParams::new(letters).unwrap();
+        params.adjacency = AdjacencyRule::AdjacentSides;
+        assert!(matches!(
+            Solver::try_solve(params, &lexicon),
+            Err(SolutionError::GENERAL)
+        ));
+
+        // ... but a hexagon leaves three sides to play onto
+        let mut params = SolveParams::from_sides(&["rv", "he", "ai", "pn", "wg", "mo"]).unwrap();
+        params.adjacency = AdjacencyRule::AdjacentSides;
+        let topology = params.topology().unwrap();
+        let solution = Solver::try_solve(params, &lexicon).unwrap();
+        assert!(verify_on(letters, &topology, solution.words(), &lexicon).is_ok());
+
+        // Forbidden pairs and predicates are never played
+        let mut params = SolveParams::new(letters).unwrap();
+        params.forbidden = vec!["5-10".to_string()];
+        params.forbids = Some(AdjacencyPredicate::new(|a, b| {
+            a / 3 == b / 3 || a + b == 12
+        }));
+        let topology = params.topology().unwrap();
+        assert!(topology.forbids(10, 5) && topology.forbids(4, 8));
+        let solution = Solver::try_solve(params, &lexicon).unwrap();
+        let verified = verify_on(letters, &topology, solution.words(), &lexicon).unwrap();
+        for trajectory in verified.trajectories() {
+            for pair in trajectory.windows(2) {
+                assert!(!topology.forbids(pair[0], pair[1]));
+            }
+        }
+
+        // A predicate allowing every move still can't play a letter twice
+        let mut params = SolveParams::new(letters).unwrap();
+        params.forbids = Some(AdjacencyPredicate::new(|_, _| false));
+        let topology = params.topology().unwrap();
+        assert!(can_make_word_on("mo", letters, &topology).next().is_some());
+        assert!(can_make_word_on("moo", letters, &topology).next().is_none());
+
+        let mut params = SolveParams::new(letters).unwrap();
+        params.forbidden = vec!["5:10".to_string()];
+        assert!(matches!(params.validate(), Err(ParamsError::Adjacency(_))));
+        params.forbidden = vec!["5-12".to_string()];
+        assert!(matches!(params.validate(), Err(ParamsError::Adjacency(_))));
+    }
+
+    #[test]
+    fn tiles_solve() {
+        let tiles = ["th", "r", "i", "qu", "e", "a", "l", "n", "o", "s", "c", "t"];
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        let params = SolveParams::from_tiles(&tiles).unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.size(), 12);
+        let topology = params.topology().unwrap();
+        let solution = Solver::try_solve(params, &lexicon).unwrap();
+        let tiles: Vec<String> = tiles.map(String::from).to_vec();
+        assert!(verify_tiles(&tiles, &topology, solution.words(), &lexicon).is_ok());
+
+        // Each tile is a single position to cover
+        let full = solution.states().last().unwrap();
+        assert_eq!(full.score(), tiles.len());
+        assert!(solution.uncovered_tiles(&tiles).is_empty());
+
+        // Sides are made of whole tiles
+        let mut params = SolveParams::from_tiles(&tiles).unwrap();
+        params.sides = ["thri", "quea", "lno", "sct"].map(String::from).to_vec();
+        assert_eq!(params.topology().unwrap().lengths(), &vec![3, 3, 3, 3]);
+        params.sides = ["thriq", "uea", "lno", "sct"].map(String::from).to_vec();
+        assert!(matches!(params.validate(), Err(ParamsError::GameSize(_))));
+
+        let mut params = SolveParams::from_tiles(&tiles).unwrap();
+        params.letters = "thriqueal".to_string();
+        assert!(matches!(params.validate(), Err(ParamsError::Tiles(_))));
+    }
+
+    #[test]
+    fn constrained_solve() {
+        // March 12 '24
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let params = || SolveParams::new(letters).unwrap();
+        let solve = |params: SolveParams| {
+            assert!(params.validate().is_ok());
+            let solution = Solver::try_solve(params, &lexicon).unwrap();
+            assert!(verify(letters, solution.words(), &lexicon).is_ok());
+            solution.words().clone()
+        };
+
+        // Word lengths and banned words
+        let words = solve(SolveParams {
+            max_word_len: Some(6),
+            ..params()
+        });
+        assert!(words.iter().all(|w| w.len() <= 6));
+        let words = solve(SolveParams {
+            min_word_len: Some(7),
+            ..params()
+        });
+        assert!(words.iter().all(|w| w.len() >= 7));
+        let words = solve(SolveParams {
+            banned: vec!["improving".to_string()],
+            ..params()
+        });
+        assert!(!words.contains(&"improving".to_string()));
+
+        // Required words are played, and their states only cover the board
+        let params_required = SolveParams {
+            required: vec!["hero".to_string()],
+            ..params()
+        };
+        let solution = Solver::try_solve(params_required, &lexicon).unwrap();
+        assert!(solution.words().contains(&"hero".to_string()));
+        assert_eq!(solution.states().last().unwrap().score(), letters.len());
+
+        // The fewest words the game can take
+        let words = solve(SolveParams {
+            max_game_words: Some(3),
+            ..params()
+        });
+        assert!(words.len() <= 3);
+        let too_few = SolveParams {
+            max_game_words: Some(2),
+            ..params()
+        };
+        assert!(too_few.validate().is_ok());
+        assert!(matches!(
+            Solver::try_solve(too_few, &lexicon),
+            Err(SolutionError::GENERAL)
+        ));
+
+        // Contradictions are rejected
+        let contradictions = [
+            SolveParams {
+                min_word_len: Some(6),
+                max_word_len: Some(5),
+                ..params()
+            },
+            SolveParams {
+                banned: vec!["hero".to_string()],
+                required: vec!["hero".to_string()],
+                ..params()
+            },
+            SolveParams {
+                min_word_len: Some(5),
+                required: vec!["hero".to_string()],
+                ..params()
+            },
+            SolveParams {
+                max_game_words: Some(1),
+                required: vec!["hero".to_string(), "german".to_string()],
+                ..params()
+            },
+            SolveParams {
+                max_game_words: Some(1),
+                played: vec!["improving".to_string(), "german".to_string()],
+                ..params()
+            },
+        ];
+        for params in contradictions {
+            assert!(matches!(
+                params.validate(),
+                Err(ParamsError::Constraints(_))
+            ));
+        }
+        let greedy = SolveParams {
+            max_game_words: Some(3),
+            strategy: Some(StrategyKind::BestFirst),
+            ..params()
+        };
+        assert!(matches!(greedy.validate(), Err(ParamsError::Strategy(_))));
+
+        // Required words must be words, and playable on the board
+        for word in ["qzx", "zebra"] {
+            let params = SolveParams {
+                required: vec![word.to_string()],
+                ..params()
+            };
+            assert!(params.validate().is_ok());
+            assert!(matches!(
+                params.validate_words(&lexicon),
+                Err(ParamsError::Constraints(_))
+            ));
+        }
+
+        // A constrained failure says nothing about the board itself
+        let result = SolutionResult::from_params(
+            SolveParams {
+                max_game_words: Some(2),
+                ..params()
+            },
+            &lexicon,
+        );
+        assert!(matches!(
+            result.meta.status,
+            SolutionStatus::FAIL(SolutionError::GENERAL)
+        ));
+        assert!(result.diagnosis.is_none());
+
+        // Pangrams the constraints rule out aren't listed
+        let pangram_board = || SolveParams::new("cmlopietrnay").unwrap();
+        let result = SolutionResult::from_params(pangram_board(), &lexicon);
+        assert!(result.pangram);
+        let result = SolutionResult::from_params(
+            SolveParams {
+                max_word_len: Some(12),
+                ..pangram_board()
+            },
+            &lexicon,
+        );
+        assert!(!result.pangram);
+        assert!(result.pangrams.is_empty());
+    }
+
+    #[test]
+    fn perfect_solve() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let params = SolveParams {
+            mode: SolveMode::Perfect,
+            ..SolveParams::new(letters).unwrap()
+        };
+        assert!(params.validate().is_ok());
+        let perfect = Solver::try_solve(params, &lexicon).unwrap();
+        assert_eq!(perfect.words(), &vec!["tables", "scoring"]);
+        assert_eq!(perfect.path().reuse(), 0);
+        assert!(verify(letters, perfect.words(), &lexicon).is_ok());
+
+        // Every letter is played once, besides the joins
+        let played: usize = perfect.words().iter().map(|w| w.len()).sum();
+        assert_eq!(played, letters.len() + perfect.words().len() - 1);
+
+        // Other solutions can be ranked by how much they reuse
+        let ranked = Solver::top_k(
+            SolveParams::new(letters).unwrap(),
+            &lexicon,
+            2,
+            3,
+            LeastReuse,
+        )
+        .unwrap();
+        assert_eq!(ranked[0].cost(), 0.0);
+        assert_eq!(ranked[0].solution().words(), perfect.words());
+        assert!(ranked[1].cost() > 0.0);
+        assert_eq!(ranked[1].solution().reuse(), ranked[1].cost() as usize);
+
+        // A board without a perfect solution can still be solvable, so its
+        // failure isn't diagnosed
+        let result = SolutionResult::from_params(
+            SolveParams {
+                mode: SolveMode::Perfect,
+                ..SolveParams::new("rvheaipnwgmq").unwrap()
+            },
+            &lexicon,
+        );
+        assert!(matches!(
+            result.meta.status,
+            SolutionStatus::FAIL(SolutionError::GENERAL)
+        ));
+        assert!(result.diagnosis.is_none());
+    }
+
+    #[test]
+    fn wildcard_solve() {
+        // March 12 '24, with the W replaced by a wildcard
+        let letters = "rvheaipn?gmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let params = SolveParams::new(letters).unwrap();
+        assert!(params.validate().is_ok());
+        let (tiles, topology) = (params.tiles(), params.topology().unwrap());
+
+        let solution = Solver::try_solve(params, &lexicon).unwrap();
+        assert!(verify_tiles(&tiles, &topology, solution.words(), &lexicon).is_ok());
+
+        // Each wildcard is reported with the letter it stood for
+        let substitutions = solution.substitutions(&tiles, &topology);
+        assert!(!substitutions.is_empty());
+        for substitution in &substitutions {
+            assert_eq!(substitution.position(), 8);
+            assert!(solution.words().contains(substitution.word()));
+            assert!(substitution.word().contains(substitution.letter()));
+        }
+
+        // Among the shortest solutions, one playing the wildcard the fewest
+        // times wins (rehab, improving plays it twice; improving, gather once),
+        // whichever optimal strategy finds it
+        for letters in [letters, "caitns?lgbre"] {
+            for strategy in [StrategyKind::Bfs, StrategyKind::AStar] {
+                let params = SolveParams {
+                    mode: SolveMode::MinWords,
+                    strategy: Some(strategy),
+                    ..SolveParams::new(letters).unwrap()
+                };
+                let (tiles, topology) = (params.tiles(), params.topology().unwrap());
+                let minimal = Solver::try_solve(params, &lexicon).unwrap();
+                assert_eq!(minimal.words().len(), 2);
+                assert_eq!(minimal.substitutions(&tiles, &topology).len(), 1);
+            }
+        }
+
+        // Plays spelled with fewer wildcards are tried first
+        let index: BoardIndex<u64> = SolveParams::new(letters).unwrap().index(&lexicon).unwrap();
+        for start in 0..index.size() {
+            let wildcards: Vec<usize> = index
+                .plays_from(start)
+                .iter()
+                .map(|p| p.wildcards)
+                .collect();
+            assert!(wildcards.is_sorted());
+        }
+
+        // ... and solutions can be ranked by the wildcards they play
+        let objective = FewestWildcards::new(tiles.clone(), topology.clone());
+        assert_eq!(objective.cost(&solution.path()), substitutions.len() as f64);
+
+        // A wildcard can't share a tile
+        let params = SolveParams::from_tiles(&["th", "r", "i", "?u", "e", "a", "l", "n"]).unwrap();
+        assert!(matches!(params.validate(), Err(ParamsError::Tiles(_))));
+
+        // Boards without wildcards report none
+        let letters = "rvheaipnwgmo";
+        let solution = Solver::try_solve(SolveParams::new(letters).unwrap(), &lexicon).unwrap();
+        let tiles = SolveParams::new(letters).unwrap().tiles();
+        assert!(solution.substitutions(&tiles, &topology).is_empty());
+    }
+
+    #[test]
+    fn mid_game_solve() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let played = |words: &[&str]| SolveParams {
+            mode: SolveMode::MinWords,
+            played: words.iter().map(|w| w.to_string()).collect(),
+            ..SolveParams::new(letters).unwrap()
+        };
+
+        // The search picks up from the state the played words leave
+        let params = played(&["improving"]);
+        assert!(params.validate().is_ok());
+        let solution = Solver::solve(params, &lexicon).unwrap();
+        let start = &solution.states()[0];
+        assert_eq!(start.index(), 9);
+        assert_eq!(format!("{:b}", start.state()), "111011100011");
+        assert_eq!(solution.words().len(), 2);
+        assert!(solution.words()[0].starts_with('g'));
+
+        // ... which can also be given explicitly
+        let params = SolveParams {
+            mode: SolveMode::MinWords,
+            start_index: Some(9),
+            start_coverage: Some("111011100011".to_string()),
+            ..SolveParams::new(letters).unwrap()
+        };
+        assert!(params.validate().is_ok());
+        let explicit = Solver::solve(params, &lexicon).unwrap();
+        assert_eq!(explicit.words(), solution.words());
+
+        let solution = Solver::solve(played(&["improving", "german"]), &lexicon).unwrap();
+        assert_eq!(solution.words().len(), 1);
+
+        // Enumeration picks up from the same state, yielding only the words
+        // still to play
+        let solutions: Vec<_> = Solver::enumerate(played(&["improving"]), &lexicon, 2)
+            .unwrap()
+            .collect();
+        assert!(!solutions.is_empty());
+        for solution in &solutions {
+            assert_eq!(solution.states()[0], *start);
+            assert!(solution.words()[0].starts_with('g'));
+        }
+        assert!(solutions
+            .iter()
+            .any(|s| s.words() == &vec!["german".to_string(), "nowhere".to_string()]));
+
+        // Played words must be traceable and chain together
+        let params = played(&["improving", "nowhere"]);
+        assert!(matches!(params.validate(), Err(ParamsError::Played(_))));
+        let params = played(&["zebra"]);
+        assert!(matches!(params.validate(), Err(ParamsError::Played(_))));
+        let params = played(&["improving", "", "german"]);
+        assert!(matches!(params.validate(), Err(ParamsError::Played(_))));
+
+        // Explicit states must be consistent
+        let params = SolveParams {
+            start_index: Some(0),
+            start_coverage: Some("111011100010".to_string()),
+            ..SolveParams::new(letters).unwrap()
+        };
+        assert!(matches!(params.validate(), Err(ParamsError::Start(_))));
+        let params = SolveParams {
+            start_index: Some(9),
+            ..played(&["improving"])
+        };
+        assert!(matches!(params.validate(), Err(ParamsError::Start(_))));
+    }
+
+    #[test]
+    fn partial_solutions() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Running out of expansions keeps the path with the highest coverage
+        let params = SolveParams {
+            mode: SolveMode::MinWords,
+            max_expansions: Some(20),
+            ..SolveParams::new(letters).unwrap()
+        };
+        let SolveOutcome::Partial(SolutionError::BUDGET, partial) =
+            Solver::attempt(params, &lexicon)
+        else {
+            panic!("Expected a partial solution");
+        };
+        assert!(!partial.words().is_empty());
+        assert_eq!(partial.states().len(), partial.words().len() + 1);
+        assert_eq!(partial.optimality(), Optimality::UNPROVEN);
+        let covered = partial.states().last().unwrap().score();
+        assert!(covered > 1);
+        assert_eq!(partial.uncovered(letters).len(), letters.len() - covered);
+
+        // Running out of time does the same, with its own reason
+        let params = SolveParams {
+            max_duration: Some(0),
+            ..SolveParams::new(letters).unwrap()
+        };
+        let outcome = Solver::attempt(params, &lexicon);
+        assert!(matches!(
+            outcome,
+            SolveOutcome::Partial(SolutionError::TIMEOUT, _)
+        ));
+
+        // Cancelling from another thread does the same, with its own reason
+        let cancel = CancelToken::new();
+        let params = SolveParams {
+            cancel: Some(cancel.clone()),
+            ..SolveParams::new(letters).unwrap()
+        };
+        std::thread::spawn(move || cancel.cancel()).join().unwrap();
+        let outcome = Solver::attempt(params, &lexicon);
+        assert!(matches!(
+            outcome,
+            SolveOutcome::Partial(SolutionError::CANCELLED, _)
+        ));
+
+        // A solve within its limits is unaffected
+        let params = SolveParams {
+            max_duration: Some(60_000),
+            ..SolveParams::new(letters).unwrap()
+        };
+        let solution = Solver::attempt(params, &lexicon).into_result().unwrap();
+        assert!(solution.uncovered(letters).is_empty());
+    }
 }
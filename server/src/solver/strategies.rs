@@ -0,0 +1,646 @@
+//! # Strategies
+//!
+//! Pluggable search strategies over a `Graph`.
+//!
+//! Every strategy starts from the nodes already in the graph (usually one
+//! per letter, see `Graph::from_letters`), expands nodes into the graph as it
+//! goes and stops at the first node that covers every letter.
+//!
+//! Nodes are only expanded with words that cover a new letter (see
+//! `BoardIndex::successors`), so "fewest words" below means the fewest among
+//! such chains: a word played only to move to another letter is never tried.
+//!
+//! - `BestFirst`: expands the node with the highest score first (the default).
+//! - `Bfs`: expands one word at a time, so the path found has the fewest words.
+//! - `AStar`: expands by words played plus an admissible estimate of the words
+//!   still needed, so the path found has the fewest words.
+//! - `Beam`: expands one word at a time, keeping only the best nodes per level.
+//! - `Annealing`: random walks that favor covering new letters (see `annealing`).
+//! - `ParallelBfs`: `Bfs` with each level expanded across threads (see
+//!   `parallel`, only with the `parallel` feature).
+//!
+//! On a board with wildcards, the strategies finding the fewest words (`Bfs`,
+//! `AStar` and `ParallelBfs`) break ties by the fewest wildcards played. The
+//! others return the first covering path they reach.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::graph::Graph;
+//! use letter_boxed::solver::index::BoardIndex;
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::strategies::{Beam, Limits, SearchStrategy};
+//!
+//! let letters = "rvheaipnwgmo";
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let index = BoardIndex::new(letters, &lexicon);
+//! let mut graph = Graph::from_letters(letters);
+//!
+//! let outcome = Beam::new(16).search(&mut graph, &index, &Limits::default());
+//! println!("{:?}", outcome);
+//! ```
+
+#[cfg(feature = "parallel")]
+use super::parallel::ParallelBfs;
+use super::{
+    annealing::Annealing,
+    coverage::CoverageState,
+    graph::{Graph, Node, NodeID, SearchOutcome, StopReason},
+    index::BoardIndex,
+};
+use num::BigUint;
+use serde::Deserialize;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// A token for cancelling a search from another thread
+///
+/// Clones share the same state, so cancelling any of them cancels the
+/// searches checking the others.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a new token that has not been cancelled
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Asks every search checking this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Returns a guard that cancels the token when dropped, tying a search
+    /// to the lifetime of whatever holds the guard
+    pub fn drop_guard(&self) -> CancelGuard {
+        CancelGuard(self.clone())
+    }
+}
+
+/// Cancels its token when dropped (see `CancelToken::drop_guard`)
+#[derive(Debug)]
+pub struct CancelGuard(CancelToken);
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Limits on how much work a search may do before giving up
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    /// Maximum number of nodes to expand
+    pub max_expansions: Option<usize>,
+    /// Instant after which the search gives up
+    pub deadline: Option<Instant>,
+    /// Token that stops the search once cancelled
+    pub cancel: Option<CancelToken>,
+}
+
+impl Limits {
+    /// Creates limits on the number of expansions and on the time spent
+    /// searching, starting now
+    pub fn new(max_expansions: Option<usize>, max_duration: Option<Duration>) -> Limits {
+        Limits {
+            max_expansions,
+            deadline: max_duration.map(|duration| Instant::now() + duration),
+            cancel: None,
+        }
+    }
+
+    /// Creates limits on the number of expansions only
+    pub fn expansions(max_expansions: usize) -> Limits {
+        Limits::new(Some(max_expansions), None)
+    }
+
+    /// Stops the search once the given token is cancelled
+    pub fn with_cancel(mut self, cancel: Option<CancelToken>) -> Limits {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Returns whether the deadline has passed
+    pub fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Returns whether the search has been cancelled
+    pub fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+    }
+
+    /// Returns why the search must stop, however many nodes it expanded
+    pub fn interruption(&self) -> Option<StopReason> {
+        if self.cancelled() {
+            Some(StopReason::Cancelled)
+        } else if self.timed_out() {
+            Some(StopReason::Timeout)
+        } else {
+            None
+        }
+    }
+
+    /// Returns why a search that has expanded `expansions` nodes must stop
+    pub fn stop_reason(&self, expansions: usize) -> Option<StopReason> {
+        self.interruption().or_else(|| {
+            self.max_expansions
+                .is_some_and(|max| expansions >= max)
+                .then_some(StopReason::Expansions)
+        })
+    }
+
+    /// Returns whether the search must stop, however many nodes it expanded
+    pub fn interrupted(&self) -> bool {
+        self.interruption().is_some()
+    }
+
+    /// Returns whether a search that has expanded `expansions` nodes must stop
+    pub fn exceeded(&self, expansions: usize) -> bool {
+        self.stop_reason(expansions).is_some()
+    }
+}
+
+/// A strategy for searching a graph for a path that covers every letter,
+/// where node coverage is stored as `S`
+pub trait SearchStrategy<S: CoverageState = BigUint> {
+    /// Searches from the nodes already in `graph`, adding the nodes and edges
+    /// it explores from the plays in `index`. Gives up with
+    /// `SearchOutcome::Budget` once any of the `limits` is exceeded, saying
+    /// which.
+    fn search<'a>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S>;
+
+    /// Whether a path found by this strategy is guaranteed to use the fewest
+    /// words of any chain whose every word covers a new letter
+    fn is_optimal(&self) -> bool {
+        false
+    }
+}
+
+/// A strategy that can search boards of any size, whichever coverage state
+/// the solver picks for them
+pub trait AnyStrategy:
+    SearchStrategy<u64> + SearchStrategy<u128> + SearchStrategy<BigUint>
+{
+}
+
+impl<T> AnyStrategy for T where
+    T: SearchStrategy<u64> + SearchStrategy<u128> + SearchStrategy<BigUint> + ?Sized
+{
+}
+
+/// The built-in search strategies, as chosen in `SolveParams`
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    BestFirst,
+    Bfs,
+    AStar,
+    Beam,
+    Annealing,
+    #[cfg(feature = "parallel")]
+    ParallelBfs,
+}
+
+impl StrategyKind {
+    /// Builds the strategy (`beam_width` is only used by `Beam`, and `seed`
+    /// only by `Annealing`). `ParallelBfs` uses every available thread and
+    /// makes no guarantee on which of several shortest paths it returns.
+    pub fn build(&self, beam_width: usize, seed: u64) -> Box<dyn AnyStrategy> {
+        match self {
+            Self::BestFirst => Box::new(BestFirst),
+            Self::Bfs => Box::new(Bfs),
+            Self::AStar => Box::new(AStar),
+            Self::Beam => Box::new(Beam::new(beam_width)),
+            Self::Annealing => Box::new(Annealing::new(seed)),
+            #[cfg(feature = "parallel")]
+            Self::ParallelBfs => Box::new(ParallelBfs::default()),
+        }
+    }
+
+    /// Whether the strategy is guaranteed to find a path with the fewest
+    /// words (see `SearchStrategy::is_optimal`)
+    pub fn is_optimal(&self) -> bool {
+        match self {
+            Self::Bfs | Self::AStar => true,
+            #[cfg(feature = "parallel")]
+            Self::ParallelBfs => true,
+            _ => false,
+        }
+    }
+}
+
+/// Returns a starting node that already covers every letter, if any
+pub(crate) fn covered_start<S: CoverageState>(
+    graph: &Graph<S>,
+    max_score: usize,
+) -> Option<SearchOutcome<S>> {
+    let node = graph.nodes().iter().find(|n| n.score() == max_score)?;
+    Some(SearchOutcome::Found(vec![node.id().clone()]))
+}
+
+/// Greedy best-first search, ordered by `Node`'s ordering (highest score first)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BestFirst;
+
+impl<S: CoverageState> SearchStrategy<S> for BestFirst {
+    fn search<'a>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        let max_score = index.goal();
+        let mut queue: BinaryHeap<Node<S>> = graph.nodes().iter().cloned().collect();
+        let mut visited: HashSet<NodeID<S>> = HashSet::new();
+        let mut parents: HashMap<NodeID<S>, NodeID<S>> = HashMap::new();
+        let mut expansions = 0;
+
+        while let Some(node) = queue.pop() {
+            if visited.contains(node.id()) {
+                continue;
+            }
+            visited.insert(node.id().clone());
+
+            if node.score() == max_score {
+                return SearchOutcome::Found(Graph::trace_path(&parents, node.id()));
+            }
+            if let Some(reason) = limits.stop_reason(expansions) {
+                return SearchOutcome::Budget(reason);
+            }
+            expansions += 1;
+
+            let new_nodes = graph.generate_edges_for_node(node.id(), index).unwrap();
+            new_nodes.into_iter().for_each(|n| {
+                parents.insert(n.id().clone(), node.id().clone());
+                queue.push(n)
+            });
+        }
+        SearchOutcome::Exhausted
+    }
+}
+
+/// Breadth-first search, one word at a time
+///
+/// On a board with wildcards, the path found plays the fewest of them among
+/// the paths with the fewest words.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bfs;
+
+impl<S: CoverageState> SearchStrategy<S> for Bfs {
+    fn search<'a>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        let max_score = index.goal();
+        if let Some(outcome) = covered_start(graph, max_score) {
+            return outcome;
+        }
+        if index.has_wildcards() {
+            return Self::search_fewest_wildcards(graph, index, limits);
+        }
+        let mut frontier: Vec<NodeID<S>> = graph.nodes().iter().map(|n| n.id().clone()).collect();
+        let mut parents: HashMap<NodeID<S>, NodeID<S>> = HashMap::new();
+        let mut expansions = 0;
+
+        // Each level of the search corresponds to one more word in the path
+        while !frontier.is_empty() {
+            let mut next_frontier = vec![];
+            for id in &frontier {
+                if let Some(reason) = limits.stop_reason(expansions) {
+                    return SearchOutcome::Budget(reason);
+                }
+                expansions += 1;
+
+                // Nodes are only returned the first time they are discovered,
+                // which is always at their shallowest level
+                let new_nodes = graph.generate_edges_for_node(id, index).unwrap();
+                for n in new_nodes {
+                    parents.insert(n.id().clone(), id.clone());
+                    if n.score() == max_score {
+                        return SearchOutcome::Found(Graph::trace_path(&parents, n.id()));
+                    }
+                    next_frontier.push(n.id().clone());
+                }
+            }
+            frontier = next_frontier;
+        }
+        SearchOutcome::Exhausted
+    }
+
+    fn is_optimal(&self) -> bool {
+        true
+    }
+}
+
+impl Bfs {
+    /// Searches like `Bfs::search` on a board with wildcards, breaking ties
+    /// between paths of the same number of words by the fewest wildcards
+    /// played.
+    ///
+    /// Each node keeps the parent reaching it with the fewest wildcards among
+    /// those on the level before it, and the level that first covers every
+    /// letter is finished before picking the covering node played with the
+    /// fewest.
+    fn search_fewest_wildcards<'a, S: CoverageState>(
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        let max_score = index.goal();
+        let mut frontier: Vec<NodeID<S>> = graph.nodes().iter().map(|n| n.id().clone()).collect();
+        let mut parents: HashMap<NodeID<S>, NodeID<S>> = HashMap::new();
+        // Fewest wildcards played to reach each node of the frontier
+        let mut wildcards: HashMap<NodeID<S>, usize> =
+            frontier.iter().map(|id| (id.clone(), 0)).collect();
+        let mut expansions = 0;
+
+        while !frontier.is_empty() {
+            let mut next_frontier = vec![];
+            let mut next_wildcards: HashMap<NodeID<S>, usize> = HashMap::new();
+            let mut goals = vec![];
+            // The fewest wildcards any goal on this level was reached with
+            let mut best: Option<usize> = None;
+            for id in &frontier {
+                if best.is_some_and(|best| wildcards[id] >= best) {
+                    continue;
+                }
+                if let Some(reason) = limits.stop_reason(expansions) {
+                    return SearchOutcome::Budget(reason);
+                }
+                expansions += 1;
+
+                let node = graph.get_node(id).unwrap().clone();
+                let mut successors = index.weighted_successors(&node);
+                // Past the first goal, only other goals can matter
+                if best.is_some() {
+                    successors.retain(|(_, n, _)| n.score() == max_score);
+                }
+                let new_nodes: HashSet<NodeID<S>> = graph
+                    .add_successors(
+                        id,
+                        successors.iter().map(|(w, n, _)| (*w, n.clone())).collect(),
+                    )
+                    .into_iter()
+                    .map(|n| n.id().clone())
+                    .collect();
+                for (_, n, played) in successors {
+                    let reached = wildcards[id] + played;
+                    match next_wildcards.get_mut(n.id()) {
+                        // Found again on the same level, maybe with fewer
+                        Some(fewest) if reached < *fewest => {
+                            *fewest = reached;
+                            if n.score() == max_score {
+                                best = Some(best.map_or(reached, |best| best.min(reached)));
+                            }
+                            parents.insert(n.id().clone(), id.clone());
+                        }
+                        Some(_) => (),
+                        // Found for the first time, at its shallowest level
+                        None if new_nodes.contains(n.id()) => {
+                            next_wildcards.insert(n.id().clone(), reached);
+                            parents.insert(n.id().clone(), id.clone());
+                            if n.score() == max_score {
+                                goals.push(n.id().clone());
+                                best = Some(best.map_or(reached, |best| best.min(reached)));
+                            }
+                            next_frontier.push(n.id().clone());
+                        }
+                        None => (),
+                    }
+                }
+            }
+            if let Some(goal) = goals.iter().min_by_key(|id| next_wildcards[*id]) {
+                return SearchOutcome::Found(Graph::trace_path(&parents, goal));
+            }
+            frontier = next_frontier;
+            wildcards = next_wildcards;
+        }
+        SearchOutcome::Exhausted
+    }
+}
+
+/// An entry of the A* queue: (estimated words, wildcards played, letters
+/// covered, position of the node in the arena)
+type AStarEntry = (Reverse<usize>, Reverse<usize>, usize, Reverse<usize>);
+
+/// A* search over the number of words played
+///
+/// The heuristic assumes every remaining word covers as many new letters as
+/// the widest play on the board possibly could (all but its first letter,
+/// which is shared with the previous word). It never overestimates the number
+/// of words left, so the first path found has the fewest words.
+///
+/// Like `Bfs`, ties between paths of the same number of words go to the one
+/// playing the fewest wildcards: paths are costed by words, then wildcards.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AStar;
+
+impl<S: CoverageState> SearchStrategy<S> for AStar {
+    fn search<'a>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        let max_score = index.goal();
+        let max_gain = index.max_play_coverage().saturating_sub(1).max(1);
+        let heuristic = |node: &Node<S>| (max_score - node.score()).div_ceil(max_gain);
+
+        // The lowest estimate comes first, then the fewest wildcards, with
+        // remaining ties going to the node covering the most
+        let mut arena: Vec<Node<S>> = graph.nodes().clone();
+        let mut queue: BinaryHeap<AStarEntry> = arena
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (Reverse(heuristic(n)), Reverse(0), n.score(), Reverse(i)))
+            .collect();
+        // The fewest (words, wildcards) each node has been reached with
+        let mut costs: HashMap<NodeID<S>, (usize, usize)> =
+            arena.iter().map(|n| (n.id().clone(), (0, 0))).collect();
+        let mut closed: HashSet<NodeID<S>> = HashSet::new();
+        let mut parents: HashMap<NodeID<S>, NodeID<S>> = HashMap::new();
+        let mut expansions = 0;
+
+        while let Some((_, _, _, Reverse(i))) = queue.pop() {
+            let node = arena[i].clone();
+            if !closed.insert(node.id().clone()) {
+                continue;
+            }
+            if node.score() == max_score {
+                return SearchOutcome::Found(Graph::trace_path(&parents, node.id()));
+            }
+            if let Some(reason) = limits.stop_reason(expansions) {
+                return SearchOutcome::Budget(reason);
+            }
+            expansions += 1;
+
+            let (words, wildcards) = costs[node.id()];
+            let successors = index.weighted_successors(&node);
+            graph.add_successors(
+                node.id(),
+                successors.iter().map(|(w, n, _)| (*w, n.clone())).collect(),
+            );
+            for (_, n, played) in successors {
+                let cost = (words + 1, wildcards + played);
+                if costs.get(n.id()).is_some_and(|c| *c <= cost) {
+                    continue;
+                }
+                costs.insert(n.id().clone(), cost);
+                parents.insert(n.id().clone(), node.id().clone());
+                queue.push((
+                    Reverse(cost.0 + heuristic(&n)),
+                    Reverse(cost.1),
+                    n.score(),
+                    Reverse(arena.len()),
+                ));
+                arena.push(n);
+            }
+        }
+        SearchOutcome::Exhausted
+    }
+
+    fn is_optimal(&self) -> bool {
+        true
+    }
+}
+
+/// Beam search, one word at a time, keeping the `width` highest-scoring
+/// nodes of each level
+#[derive(Debug, Clone, Copy)]
+pub struct Beam {
+    width: usize,
+}
+
+impl Beam {
+    /// Creates a new beam search keeping `width` nodes per level
+    pub fn new(width: usize) -> Beam {
+        Beam {
+            width: width.max(1),
+        }
+    }
+}
+
+impl<S: CoverageState> SearchStrategy<S> for Beam {
+    fn search<'a>(
+        &self,
+        graph: &mut Graph<'a, S>,
+        index: &BoardIndex<'a, S>,
+        limits: &Limits,
+    ) -> SearchOutcome<S> {
+        let max_score = index.goal();
+        if let Some(outcome) = covered_start(graph, max_score) {
+            return outcome;
+        }
+        let mut frontier: Vec<Node<S>> = graph.nodes().clone();
+        let mut parents: HashMap<NodeID<S>, NodeID<S>> = HashMap::new();
+        let mut expansions = 0;
+
+        while !frontier.is_empty() {
+            let mut next_frontier = vec![];
+            for node in &frontier {
+                if let Some(reason) = limits.stop_reason(expansions) {
+                    return SearchOutcome::Budget(reason);
+                }
+                expansions += 1;
+
+                let new_nodes = graph.generate_edges_for_node(node.id(), index).unwrap();
+                for n in new_nodes {
+                    parents.insert(n.id().clone(), node.id().clone());
+                    if n.score() == max_score {
+                        return SearchOutcome::Found(Graph::trace_path(&parents, n.id()));
+                    }
+                    next_frontier.push(n);
+                }
+            }
+            // Keep only the most promising nodes for the next level
+            next_frontier.sort_by_key(|n| Reverse(n.score()));
+            next_frontier.truncate(self.width);
+            frontier = next_frontier;
+        }
+        SearchOutcome::Exhausted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AStar, Beam, BestFirst, Bfs, CancelToken, Limits, SearchStrategy};
+    use crate::solver::{
+        graph::{Graph, SearchOutcome, StopReason},
+        index::BoardIndex,
+        lexicon::{Lexicon, LEXICON_PATH},
+    };
+    use std::time::Duration;
+
+    fn path_len(strategy: &dyn SearchStrategy, letters: &str, lexicon: &Lexicon) -> usize {
+        let index = BoardIndex::new(letters, lexicon);
+        let mut graph = Graph::from_letters(letters);
+        match strategy.search(&mut graph, &index, &Limits::default()) {
+            SearchOutcome::Found(path) => path.len(),
+            outcome => panic!("No path found: {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn strategies_find_paths() {
+        // A board with two-word solutions (e.g. "abilities", "scoring")
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // Optimal strategies agree on the fewest words
+        assert_eq!(path_len(&Bfs, letters, &lexicon), 3);
+        assert_eq!(path_len(&AStar, letters, &lexicon), 3);
+
+        // Others find some path no shorter than the optimum
+        assert!(path_len(&BestFirst, letters, &lexicon) >= 3);
+        assert!(path_len(&Beam::new(8), letters, &lexicon) >= 3);
+    }
+
+    #[test]
+    fn strategies_respect_budget() {
+        let letters = "caitnsolgbre";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let index = BoardIndex::new(letters, &lexicon);
+        let strategies: [&dyn SearchStrategy; 4] = [&BestFirst, &Bfs, &AStar, &Beam::new(8)];
+        for strategy in strategies {
+            let mut graph = Graph::from_letters(letters);
+            let outcome = strategy.search(&mut graph, &index, &Limits::expansions(1));
+            assert_eq!(outcome, SearchOutcome::Budget(StopReason::Expansions));
+
+            // Out of time before expanding anything
+            let mut graph = Graph::from_letters(letters);
+            let limits = Limits::new(None, Some(Duration::ZERO));
+            let outcome = strategy.search(&mut graph, &index, &limits);
+            assert_eq!(outcome, SearchOutcome::Budget(StopReason::Timeout));
+
+            // Cancelled before expanding anything
+            let cancel = CancelToken::new();
+            drop(cancel.drop_guard());
+            let mut graph = Graph::from_letters(letters);
+            let limits = Limits::default().with_cancel(Some(cancel));
+            let outcome = strategy.search(&mut graph, &index, &limits);
+            assert_eq!(outcome, SearchOutcome::Budget(StopReason::Cancelled));
+        }
+    }
+}
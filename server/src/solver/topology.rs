@@ -0,0 +1,273 @@
+//! # Topology
+//!
+//! The shape of a board: how many sides it has and which positions lie on
+//! each side.
+//!
+//! The letters of a board are laid out side after side, so a side is a run
+//! of consecutive positions. The classic game has four sides of equal length,
+//! but a `BoardTopology` can describe any number of sides (a triangle, a
+//! hexagon) of any lengths.
+//!
+//! The topology also decides which positions may not be played one after the
+//! other. Under the classic rule these are any two positions on the same side,
+//! but an `AdjacencyRule`, an arbitrary predicate or individual forbidden
+//! pairs can be used instead.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::topology::{AdjacencyRule, BoardTopology};
+//!
+//! // A triangle with one long side
+//! let topology = BoardTopology::from_sides(&["abcd", "ef", "ghi"]).unwrap();
+//! assert_eq!(topology.size(), 9);
+//! assert!(topology.same_side(0, 3));
+//! assert!(!topology.same_side(3, 4));
+//!
+//! // Also forbid playing from a side onto either of its neighbours
+//! let topology = topology.with_rule(AdjacencyRule::AdjacentSides);
+//! assert!(topology.forbids(3, 4));
+//! ```
+
+use serde::Deserialize;
+use std::{fmt, sync::Arc};
+
+/// Which positions may not be played one after the other
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdjacencyRule {
+    /// Two positions on the same side (the classic rule)
+    #[default]
+    SameSide,
+    /// Two positions on the same side or on neighbouring sides (the first
+    /// and last sides are neighbours)
+    AdjacentSides,
+}
+
+/// A predicate returning whether one position may not be followed by
+/// another, which can be shared between threads
+#[derive(Clone)]
+pub struct AdjacencyPredicate(Arc<dyn Fn(usize, usize) -> bool + Send + Sync>);
+
+impl AdjacencyPredicate {
+    /// Wraps a predicate over pairs of positions
+    pub fn new<F: Fn(usize, usize) -> bool + Send + Sync + 'static>(forbids: F) -> Self {
+        AdjacencyPredicate(Arc::new(forbids))
+    }
+
+    /// Returns whether position `b` may not be played right after `a`
+    pub fn forbids(&self, a: usize, b: usize) -> bool {
+        (self.0)(a, b)
+    }
+}
+
+impl fmt::Debug for AdjacencyPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AdjacencyPredicate")
+    }
+}
+
+/// Reasons a list of sides doesn't describe a board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopologyError {
+    /// A board needs at least two sides to play across.
+    TooFewSides(usize),
+    /// Every side needs at least one letter.
+    EmptySide(usize),
+    /// A position past the end of the board.
+    OutOfBounds(usize),
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooFewSides(n) => write!(f, "A board needs at least 2 sides, not {}", n),
+            Self::EmptySide(i) => write!(f, "Side {} has no letters", i),
+            Self::OutOfBounds(i) => write!(f, "Position {} is not on the board", i),
+        }
+    }
+}
+
+/// The sides of a board, as runs of consecutive positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardTopology {
+    /// The number of letters on each side, in order.
+    lengths: Vec<usize>,
+    /// The side each position lies on.
+    sides: Vec<usize>,
+    /// Whether each position (row) may not be followed by each position
+    /// (column), flattened row by row.
+    forbidden: Vec<bool>,
+}
+
+impl BoardTopology {
+    /// Creates a topology from the number of letters on each side.
+    pub fn from_lengths(lengths: Vec<usize>) -> Result<BoardTopology, TopologyError> {
+        if lengths.len() < 2 {
+            return Err(TopologyError::TooFewSides(lengths.len()));
+        }
+        if let Some(i) = lengths.iter().position(|l| *l == 0) {
+            return Err(TopologyError::EmptySide(i));
+        }
+        let sides = lengths
+            .iter()
+            .enumerate()
+            .flat_map(|(side, len)| std::iter::repeat_n(side, *len))
+            .collect();
+        Ok(BoardTopology::with_sides(lengths, sides))
+    }
+
+    /// Creates a topology from the letters of each side.
+    pub fn from_sides<S: AsRef<str>>(sides: &[S]) -> Result<BoardTopology, TopologyError> {
+        Self::from_lengths(sides.iter().map(|s| s.as_ref().chars().count()).collect())
+    }
+
+    /// Creates the classic topology of four equal sides for `size` letters.
+    ///
+    /// Any positions left over when `size` isn't a multiple of four are
+    /// placed on a fifth side.
+    pub fn square(size: usize) -> BoardTopology {
+        let s = (size / 4).max(1);
+        let sides = (0..size).map(|i| i / s).collect();
+        let mut lengths = vec![0; size.div_ceil(s)];
+        for i in 0..size {
+            lengths[i / s] += 1;
+        }
+        BoardTopology::with_sides(lengths, sides)
+    }
+
+    /// Creates a topology under the classic same side rule
+    fn with_sides(lengths: Vec<usize>, sides: Vec<usize>) -> BoardTopology {
+        let topology = BoardTopology {
+            lengths,
+            sides,
+            forbidden: vec![],
+        };
+        topology.with_rule(AdjacencyRule::SameSide)
+    }
+
+    /// Forbids exactly the moves from one position to the next that the
+    /// predicate returns true for, replacing any forbidden before. Playing a
+    /// position twice in a row is always forbidden, whatever the predicate.
+    pub fn with_predicate<F: Fn(usize, usize) -> bool>(mut self, forbids: F) -> Self {
+        let size = self.size();
+        self.forbidden = (0..size * size)
+            .map(|i| i / size == i % size || forbids(i / size, i % size))
+            .collect();
+        self
+    }
+
+    /// Forbids exactly the moves the rule forbids, replacing any forbidden
+    /// before.
+    pub fn with_rule(self, rule: AdjacencyRule) -> Self {
+        let sides = self.sides.clone();
+        let count = self.lengths.len();
+        match rule {
+            AdjacencyRule::SameSide => self.with_predicate(|a, b| sides[a] == sides[b]),
+            AdjacencyRule::AdjacentSides => self.with_predicate(|a, b| {
+                let apart = sides[a].abs_diff(sides[b]);
+                apart <= 1 || apart == count - 1
+            }),
+        }
+    }
+
+    /// Also forbids playing either position of the pair right after the
+    /// other.
+    pub fn forbid(mut self, a: usize, b: usize) -> Result<Self, TopologyError> {
+        let size = self.size();
+        if let Some(out) = [a, b].into_iter().find(|p| *p >= size) {
+            return Err(TopologyError::OutOfBounds(out));
+        }
+        self.forbidden[a * size + b] = true;
+        self.forbidden[b * size + a] = true;
+        Ok(self)
+    }
+
+    /// Returns the number of positions on the board.
+    pub fn size(&self) -> usize {
+        self.sides.len()
+    }
+
+    /// Returns the number of letters on each side.
+    pub fn lengths(&self) -> &Vec<usize> {
+        &self.lengths
+    }
+
+    /// Returns the side the given position lies on.
+    pub fn side_of(&self, position: usize) -> usize {
+        self.sides[position]
+    }
+
+    /// Returns whether two positions lie on the same side (and so can't be
+    /// played one after the other).
+    pub fn same_side(&self, a: usize, b: usize) -> bool {
+        self.sides[a] == self.sides[b]
+    }
+
+    /// Returns whether position `b` may not be played right after `a`.
+    pub fn forbids(&self, a: usize, b: usize) -> bool {
+        self.forbidden[a * self.size() + b]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdjacencyRule, BoardTopology, TopologyError};
+
+    #[test]
+    fn board_topologies() {
+        // The classic board matches the old side rule
+        let square = BoardTopology::square(12);
+        assert_eq!(square.lengths(), &vec![3, 3, 3, 3]);
+        for a in 0..12 {
+            for b in 0..12 {
+                assert_eq!(square.same_side(a, b), a / 3 == b / 3);
+            }
+        }
+
+        // Sides of any number and length
+        let triangle = BoardTopology::from_sides(&["ab", "cde", "f"]).unwrap();
+        assert_eq!(triangle.size(), 6);
+        assert_eq!(
+            (0..6).map(|i| triangle.side_of(i)).collect::<Vec<_>>(),
+            vec![0, 0, 1, 1, 1, 2]
+        );
+        let hexagon = BoardTopology::from_lengths(vec![2; 6]).unwrap();
+        assert!(hexagon.same_side(10, 11) && !hexagon.same_side(9, 10));
+
+        assert_eq!(
+            BoardTopology::from_sides(&["abc"]),
+            Err(TopologyError::TooFewSides(1))
+        );
+        assert_eq!(
+            BoardTopology::from_lengths(vec![2, 0, 2]),
+            Err(TopologyError::EmptySide(1))
+        );
+    }
+
+    #[test]
+    fn adjacency_rules() {
+        let square = BoardTopology::square(12);
+        for a in 0..12 {
+            for b in 0..12 {
+                assert_eq!(square.forbids(a, b), square.same_side(a, b));
+            }
+        }
+
+        // Each side of the square neighbours two others
+        let adjacent = square.clone().with_rule(AdjacencyRule::AdjacentSides);
+        assert!(adjacent.forbids(0, 3) && adjacent.forbids(0, 9));
+        assert!(!adjacent.forbids(0, 6) && !adjacent.forbids(4, 10));
+
+        // Arbitrary predicates and pairs
+        let parity = square.clone().with_predicate(|a, b| a % 2 == b % 2);
+        assert!(parity.forbids(0, 2) && !parity.forbids(0, 1));
+        // ... though no position may ever follow itself
+        let anything = square.clone().with_predicate(|_, _| false);
+        assert!((0..12).all(|a| anything.forbids(a, a)));
+        assert!(!anything.forbids(0, 1));
+        let pairs = square.forbid(0, 6).unwrap();
+        assert!(pairs.forbids(0, 6) && pairs.forbids(6, 0) && pairs.forbids(0, 1));
+        assert_eq!(pairs.forbid(0, 12), Err(TopologyError::OutOfBounds(12)));
+    }
+}
@@ -0,0 +1,441 @@
+//! # Validate
+//!
+//! Check a player's sequence of words against the rules of the game.
+//!
+//! Every word is reported on separately: whether it is in the lexicon,
+//! whether it can be traced on the board, whether it starts where the previous
+//! word ended and which letters it newly covers. Any broken rule comes back as
+//! a `PlayError`, so a client can explain exactly why a word was rejected.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::validate::validate_play;
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let report = validate_play("rvheaipnwgmo", &["improving", "german"], &lexicon);
+//! for word in report.words() {
+//!     println!("{}: {:?}", word.word(), word.errors());
+//! }
+//! ```
+
+use super::{
+    coverage::CoverageState,
+    graph::Node,
+    lexicon::Lexicon,
+    topology::BoardTopology,
+    verify::{verify_on, VerifiedSolution},
+    words::{can_make_word_on, WordTrajectory, WILDCARD},
+};
+use num::BigUint;
+use serde::Serialize;
+
+/// A rule broken by a played word
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum PlayError {
+    /// The word isn't in the lexicon.
+    NotInLexicon,
+    /// The word uses a letter that isn't on the board.
+    LetterNotOnBoard,
+    /// The word can only be traced by playing two letters from the same side
+    /// in a row.
+    SameSideAdjacency,
+    /// The word can only be traced by making a move the board forbids
+    /// (other than playing along a side), such as playing a tile twice in a
+    /// row or onto a neighbouring side.
+    ForbiddenMove,
+    /// The word doesn't start on the letter the previous word ended on.
+    BrokenChain,
+}
+
+/// How a single word of a play measures up against the rules
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct WordReport {
+    word: String,
+    in_lexicon: bool,
+    /// Whether the word can be traced on the board at all.
+    traceable: bool,
+    /// Whether the word starts where the previous one ended.
+    chains: bool,
+    /// Letters covered for the first time by this word, in board order.
+    newly_covered: Vec<char>,
+    errors: Vec<PlayError>,
+}
+
+impl WordReport {
+    /// Returns the word reported on.
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// Returns whether the word is in the lexicon.
+    pub fn in_lexicon(&self) -> bool {
+        self.in_lexicon
+    }
+
+    /// Returns whether the word can be traced on the board.
+    pub fn traceable(&self) -> bool {
+        self.traceable
+    }
+
+    /// Returns whether the word starts where the previous one ended.
+    pub fn chains(&self) -> bool {
+        self.chains
+    }
+
+    /// Returns the letters covered for the first time by this word.
+    pub fn newly_covered(&self) -> &Vec<char> {
+        &self.newly_covered
+    }
+
+    /// Returns every rule the word breaks.
+    pub fn errors(&self) -> &Vec<PlayError> {
+        &self.errors
+    }
+}
+
+/// How a whole sequence of words measures up against the rules
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct PlayReport {
+    words: Vec<WordReport>,
+    /// Whether every word follows every rule.
+    valid: bool,
+    /// Letters no word has covered yet, in board order.
+    uncovered: Vec<char>,
+    /// Whether the play is valid and covers every letter.
+    solved: bool,
+    /// The play confirmed by the independent verifier, with the positions
+    /// each word is traced through, if it solves the board.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verified: Option<VerifiedSolution>,
+}
+
+impl PlayReport {
+    /// Returns the report for each word, in order.
+    pub fn words(&self) -> &Vec<WordReport> {
+        &self.words
+    }
+
+    /// Returns whether every word follows every rule.
+    pub fn valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Returns the letters no word has covered yet.
+    pub fn uncovered(&self) -> &Vec<char> {
+        &self.uncovered
+    }
+
+    /// Returns whether the play is valid and covers every letter.
+    pub fn solved(&self) -> bool {
+        self.solved
+    }
+
+    /// Returns the play as confirmed by the verifier, if it solves the board.
+    pub fn verified(&self) -> Option<&VerifiedSolution> {
+        self.verified.as_ref()
+    }
+}
+
+/// Returns the letters of the board covered by `state` but not by `before`
+fn letters_between(letters: &str, before: &BigUint, state: &BigUint) -> Vec<char> {
+    letters
+        .chars()
+        .enumerate()
+        .filter(|(i, _)| state.contains(*i) && !before.contains(*i))
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Returns why a word can't be traced on the board: a letter the board
+/// lacks (and no wildcard could stand for), or else the moves it would take
+fn untraceable_error(word: &str, letters: &str, topology: &BoardTopology) -> PlayError {
+    let on_board = |c: char| c != WILDCARD && letters.contains(c);
+    if !word.chars().all(on_board) && !letters.contains(WILDCARD) {
+        return PlayError::LetterNotOnBoard;
+    }
+    // Trace the word as if any move were allowed, and see which of the
+    // board's rules every such trajectory breaks
+    let anywhere = topology.clone().with_predicate(|_, _| false);
+    let mut trajectories = can_make_word_on(word, letters, &anywhere)
+        .filter(|t| t.len() > 0)
+        .peekable();
+    let along_side = |t: &WordTrajectory| {
+        t.indices()
+            .windows(2)
+            .any(|p| topology.same_side(p[0], p[1]) && topology.forbids(p[0], p[1]))
+    };
+    if trajectories.peek().is_some() && trajectories.all(|t| along_side(&t)) {
+        PlayError::SameSideAdjacency
+    } else {
+        PlayError::ForbiddenMove
+    }
+}
+
+/// Checks a player's words, in order, against the board and the lexicon.
+///
+/// When a word's letters appear more than once on the board, every way of
+/// tracing it is considered, and the coverage reported follows whichever
+/// covers the most letters. A word that breaks the chain still counts towards
+/// coverage, so later words are checked as if it had been accepted.
+pub fn validate_play<W: AsRef<str>>(letters: &str, words: &[W], lexicon: &Lexicon) -> PlayReport {
+    let topology = BoardTopology::square(letters.chars().count());
+    validate_play_on(letters, &topology, words, lexicon)
+}
+
+/// Checks a player's words, in order, against the board of `letters` laid
+/// out on a board of the given shape, and the lexicon.
+pub fn validate_play_on<W: AsRef<str>>(
+    letters: &str,
+    topology: &BoardTopology,
+    words: &[W],
+    lexicon: &Lexicon,
+) -> PlayReport {
+    // Every state the game can be in after the words so far
+    let mut states: Vec<Node> = vec![];
+    let mut reports = vec![];
+
+    for (i, word) in words.iter().enumerate() {
+        let word = word.as_ref();
+        let mut errors = vec![];
+
+        let in_lexicon = lexicon.contains(word);
+        if !in_lexicon {
+            errors.push(PlayError::NotInLexicon);
+        }
+
+        let trajectories: Vec<_> = can_make_word_on(word, letters, topology)
+            .filter(|t| t.len() > 0)
+            .collect();
+        let traceable = !trajectories.is_empty();
+        if !traceable {
+            errors.push(untraceable_error(word, letters, topology));
+        }
+
+        // Follow each state into each trajectory starting where it ended,
+        // keeping the state before the word alongside the state after it
+        let mut next: Vec<(BigUint, Node)> = vec![];
+        for trajectory in &trajectories {
+            let mut mask = BigUint::empty();
+            trajectory.indices().iter().for_each(|j| mask.set(*j));
+            let end = trajectory.last().unwrap();
+            let starts: Vec<&Node> = states
+                .iter()
+                .filter(|n| n.index() == trajectory.indices()[0])
+                .collect();
+            for before in starts {
+                let node = Node::new(end, before.state().union(&mask));
+                next.push((before.state().clone(), node));
+            }
+        }
+
+        let previous = i.checked_sub(1).map(|j| words[j].as_ref());
+        let chains = match previous {
+            None => true,
+            // A repeated letter on the board may be the right letter on the
+            // wrong side, so the chain is checked by position where possible
+            Some(_) if traceable && !states.is_empty() => !next.is_empty(),
+            Some(previous) => previous.chars().last() == word.chars().next(),
+        };
+        if !chains {
+            errors.push(PlayError::BrokenChain);
+        }
+
+        // Words that can't follow on still cover their letters
+        if next.is_empty() {
+            let before = states
+                .iter()
+                .map(|n| n.state().clone())
+                .max_by_key(|s| s.count())
+                .unwrap_or_default();
+            for trajectory in &trajectories {
+                let mut state = before.clone();
+                trajectory.indices().iter().for_each(|j| state.set(*j));
+                next.push((before.clone(), Node::new(trajectory.last().unwrap(), state)));
+            }
+        }
+
+        let best = next.iter().max_by_key(|(_, n)| n.score());
+        let newly_covered = best.map_or(vec![], |(before, n)| {
+            letters_between(letters, before, n.state())
+        });
+        if !next.is_empty() {
+            states.clear();
+            for (_, node) in next {
+                if !states.contains(&node) {
+                    states.push(node);
+                }
+            }
+        }
+
+        reports.push(WordReport {
+            word: word.to_string(),
+            in_lexicon,
+            traceable,
+            chains,
+            newly_covered,
+            errors,
+        });
+    }
+
+    let covered = states
+        .iter()
+        .map(|n| n.state().clone())
+        .max_by_key(|s| s.count())
+        .unwrap_or_default();
+    let uncovered: Vec<char> = letters
+        .chars()
+        .enumerate()
+        .filter(|(i, _)| !covered.contains(*i))
+        .map(|(_, c)| c)
+        .collect();
+    let valid = reports.iter().all(|r| r.errors.is_empty());
+    let solved = valid && !words.is_empty() && uncovered.is_empty();
+
+    // A solved play is only reported as such once the verifier, which
+    // traces the words independently, agrees. Plays already known not to
+    // solve the board aren't verified at all.
+    let verified = solved
+        .then(|| verify_on(letters, topology, words, lexicon).ok())
+        .flatten();
+    debug_assert_eq!(
+        solved,
+        verified.is_some(),
+        "Validation and verification disagree on {}",
+        words
+            .iter()
+            .map(|w| w.as_ref())
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    PlayReport {
+        solved: solved && verified.is_some(),
+        words: reports,
+        valid,
+        uncovered,
+        verified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_play, validate_play_on, PlayError};
+    use crate::solver::{
+        lexicon::{Lexicon, LEXICON_PATH},
+        topology::{AdjacencyRule, BoardTopology},
+        verify::{verify, VerifyError},
+    };
+
+    #[test]
+    fn validate_words() {
+        /*
+        --------------------------------
+          R V H
+        O       E
+        M       A
+        G       I
+          W N P
+        --------------------------------
+        */
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        // A full solution
+        let report = validate_play(letters, &["improving", "german", "nowhere"], &lexicon);
+        assert!(report.valid());
+        assert!(report.solved());
+        assert!(report.uncovered().is_empty());
+        assert_eq!(report.words()[1].newly_covered(), &vec!['e', 'a']);
+        assert!(report.words().iter().all(|w| w.chains() && w.traceable()));
+        let verified = report.verified().unwrap();
+        assert_eq!(
+            verified.trajectories()[0],
+            vec![5, 10, 6, 0, 11, 1, 5, 7, 9]
+        );
+
+        // Valid so far, but not finished
+        let report = validate_play(letters, &["improving"], &lexicon);
+        assert!(report.valid());
+        assert!(!report.solved());
+        assert_eq!(report.uncovered(), &vec!['h', 'e', 'a', 'w']);
+        assert!(report.verified().is_none());
+
+        // Each broken rule is reported on the word breaking it
+        let report = validate_play(letters, &["improving", "nowhere"], &lexicon);
+        assert!(!report.valid());
+        assert!(report.words()[0].errors().is_empty());
+        assert_eq!(report.words()[1].errors(), &vec![PlayError::BrokenChain]);
+        assert_eq!(report.words()[1].newly_covered(), &vec!['h', 'e', 'w']);
+
+        let report = validate_play(letters, &["zebra"], &lexicon);
+        assert_eq!(
+            report.words()[0].errors(),
+            &vec![PlayError::NotInLexicon, PlayError::LetterNotOnBoard]
+        );
+
+        // E and A share a side
+        let report = validate_play(letters, &["ear"], &lexicon);
+        assert!(report.words()[0].in_lexicon());
+        assert_eq!(
+            report.words()[0].errors(),
+            &vec![PlayError::SameSideAdjacency]
+        );
+
+        let report = validate_play(letters, &["pavo"], &lexicon);
+        assert_eq!(report.words()[0].errors(), &vec![PlayError::NotInLexicon]);
+
+        // On sides RV, HEA, IPNW and GMO, I and N share a side
+        let topology = BoardTopology::from_lengths(vec![2, 3, 4, 3]).unwrap();
+        let report = validate_play_on(letters, &topology, &["improving"], &lexicon);
+        assert_eq!(
+            report.words()[0].errors(),
+            &vec![PlayError::SameSideAdjacency]
+        );
+
+        // Other forbidden moves aren't blamed on the sides
+        let topology = BoardTopology::square(12).with_rule(AdjacencyRule::AdjacentSides);
+        let report = validate_play_on(letters, &topology, &["improving"], &lexicon);
+        assert_eq!(report.words()[0].errors(), &vec![PlayError::ForbiddenMove]);
+        // ... nor is playing the one O twice
+        let report = validate_play(letters, &["moo"], &lexicon);
+        assert!(report.words()[0]
+            .errors()
+            .contains(&PlayError::ForbiddenMove));
+
+        // A wildcard on the same side as E and A can't stand in for either
+        let letters = "rvhea?pnwgmo";
+        let report = validate_play(letters, &["ear"], &lexicon);
+        assert_eq!(
+            report.words()[0].errors(),
+            &vec![PlayError::SameSideAdjacency]
+        );
+        // ... but can for a letter that isn't on the board
+        let report = validate_play(letters, &["zebra"], &lexicon);
+        assert_eq!(
+            report.words()[0].errors(),
+            &vec![PlayError::NotInLexicon, PlayError::SameSideAdjacency]
+        );
+    }
+
+    #[test]
+    fn validate_long_unsolved_play() {
+        // With two wildcards each of these words can be traced a dozen
+        // ways, so a long play of them has an exponential number of chains
+        let letters = "rvheaipn?gm?";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let words: Vec<&str> = ["aaa", "ana"].into_iter().cycle().take(16).collect();
+
+        let report = validate_play(letters, &words, &lexicon);
+        assert!(report.valid());
+        assert!(!report.solved());
+        assert!(report.uncovered().contains(&'h'));
+        assert!(report.verified().is_none());
+
+        // The verifier only searches each state of the chain once
+        assert!(matches!(
+            verify(letters, &words, &lexicon),
+            Err(VerifyError::Uncovered(tiles)) if tiles.contains(&"h".to_string())
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+//! # Verify
+//!
+//! Check a claimed solution from scratch.
+//!
+//! The verifier shares nothing with the solver's graph: it traces each word
+//! across the board with `WordTrajectories`, checks that every word starts on
+//! the letter the previous one ended on and that together they cover every
+//! letter. It is run on the solver's own output in debug builds, and on the
+//! answers players submit for validation (see `validate`).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use letter_boxed::solver::lexicon::{Lexicon, LEXICON_PATH};
+//! use letter_boxed::solver::verify::verify;
+//!
+//! let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+//! let verified = verify("rvheaipnwgmo", &["improving", "german", "nowhere"], &lexicon);
+//! assert!(verified.is_ok());
+//! ```
+
+use super::{lexicon::Lexicon, topology::BoardTopology, words::WordTrajectories};
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+};
+
+/// Reasons a claimed solution is not a solution
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// No words were given.
+    NoWords,
+    /// The word isn't in the lexicon.
+    NotInLexicon(String),
+    /// The word can't be traced on the board.
+    Untraceable(String),
+    /// The word can't start where the previous word ended.
+    BrokenChain(String),
+    /// The words leave these tiles uncovered (however they are traced).
+    Uncovered(Vec<String>),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoWords => write!(f, "No words were given"),
+            Self::NotInLexicon(word) => write!(f, "{} is not in the lexicon", word),
+            Self::Untraceable(word) => write!(f, "{} can't be traced on the board", word),
+            Self::BrokenChain(word) => {
+                write!(f, "{} doesn't start where the previous word ended", word)
+            }
+            Self::Uncovered(tiles) => {
+                write!(f, "Letters {} are never covered", tiles.concat())
+            }
+        }
+    }
+}
+
+/// A solution confirmed to follow the rules and cover the board
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct VerifiedSolution {
+    words: Vec<String>,
+    /// The board positions each word is traced through, in order.
+    trajectories: Vec<Vec<usize>>,
+}
+
+impl VerifiedSolution {
+    /// Returns the words of the solution.
+    pub fn words(&self) -> &Vec<String> {
+        &self.words
+    }
+
+    /// Returns the board positions each word is traced through.
+    pub fn trajectories(&self) -> &Vec<Vec<usize>> {
+        &self.trajectories
+    }
+}
+
+/// The state of a search for a chain of trajectories covering the board
+struct Trace<'t> {
+    trajectories: &'t [Vec<Vec<usize>>],
+    size: usize,
+    /// The trajectory chosen for each word so far
+    chosen: Vec<usize>,
+    /// The most positions covered by a complete chain
+    best: Option<BTreeSet<usize>>,
+    /// The furthest word any chain reached
+    deepest: usize,
+    /// Partial chains already known not to complete, by the word they are
+    /// at, where they ended and the positions they cover
+    dead: HashSet<(usize, Option<usize>, BTreeSet<usize>)>,
+}
+
+impl<'t> Trace<'t> {
+    /// Chains a trajectory of the word at `depth` onto the words before it,
+    /// returning whether the chain can be completed to cover every position
+    fn search(&mut self, depth: usize, end: Option<usize>, covered: &BTreeSet<usize>) -> bool {
+        self.deepest = self.deepest.max(depth);
+        // Chains that meet in the same state go on the same way, so each
+        // state is only searched once
+        let key = (depth, end, covered.clone());
+        if self.dead.contains(&key) {
+            return false;
+        }
+        if depth == self.trajectories.len() {
+            if covered.len() == self.size {
+                return true;
+            }
+            if self.best.as_ref().is_none_or(|b| covered.len() > b.len()) {
+                self.best = Some(covered.clone());
+            }
+            return false;
+        }
+        for (i, trajectory) in self.trajectories[depth].iter().enumerate() {
+            if end.is_some_and(|end| end != trajectory[0]) {
+                continue;
+            }
+            let mut covered = covered.clone();
+            covered.extend(trajectory);
+            self.chosen.push(i);
+            if self.search(depth + 1, trajectory.last().copied(), &covered) {
+                return true;
+            }
+            self.chosen.pop();
+        }
+        self.dead.insert(key);
+        false
+    }
+}
+
+/// Checks that `words` solve the board of `letters`, using only words in
+/// `lexicon`.
+///
+/// Every way of tracing each word is tried, so boards with repeated letters
+/// are verified correctly.
+pub fn verify<W: AsRef<str>>(
+    letters: &str,
+    words: &[W],
+    lexicon: &Lexicon,
+) -> Result<VerifiedSolution, VerifyError> {
+    let topology = BoardTopology::square(letters.chars().count());
+    verify_on(letters, &topology, words, lexicon)
+}
+
+/// Checks that `words` solve the board of `letters` laid out on a board of
+/// the given shape, using only words in `lexicon`.
+pub fn verify_on<W: AsRef<str>>(
+    letters: &str,
+    topology: &BoardTopology,
+    words: &[W],
+    lexicon: &Lexicon,
+) -> Result<VerifiedSolution, VerifyError> {
+    let tiles: Vec<String> = letters.chars().map(String::from).collect();
+    verify_tiles(&tiles, topology, words, lexicon)
+}
+
+/// Checks that `words` solve a board of tiles (each of which may hold
+/// several letters) laid out on a board of the given shape, using only
+/// words in `lexicon`.
+pub fn verify_tiles<W: AsRef<str>>(
+    tiles: &[String],
+    topology: &BoardTopology,
+    words: &[W],
+    lexicon: &Lexicon,
+) -> Result<VerifiedSolution, VerifyError> {
+    if words.is_empty() {
+        return Err(VerifyError::NoWords);
+    }
+    let words: Vec<&str> = words.iter().map(|w| w.as_ref()).collect();
+
+    let mut trajectories: Vec<Vec<Vec<usize>>> = vec![];
+    for word in &words {
+        if !lexicon.contains(word) {
+            return Err(VerifyError::NotInLexicon(word.to_string()));
+        }
+        let traced: Vec<Vec<usize>> = WordTrajectories::with_tiles(word, tiles, topology)
+            .map(|t| t.indices().clone())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if traced.is_empty() {
+            return Err(VerifyError::Untraceable(word.to_string()));
+        }
+        trajectories.push(traced);
+    }
+
+    let mut trace = Trace {
+        trajectories: &trajectories,
+        size: tiles.len(),
+        chosen: vec![],
+        best: None,
+        deepest: 0,
+        dead: HashSet::new(),
+    };
+    if trace.search(0, None, &BTreeSet::new()) {
+        let chosen = trace.chosen;
+        return Ok(VerifiedSolution {
+            words: words.iter().map(|w| w.to_string()).collect(),
+            trajectories: chosen
+                .into_iter()
+                .zip(trajectories)
+                .map(|(i, mut traced)| traced.swap_remove(i))
+                .collect(),
+        });
+    }
+    match trace.best {
+        // Every chain broke somewhere, the furthest at this word
+        None => Err(VerifyError::BrokenChain(words[trace.deepest].to_string())),
+        Some(covered) => Err(VerifyError::Uncovered(
+            tiles
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !covered.contains(i))
+                .map(|(_, tile)| tile.clone())
+                .collect(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify, VerifyError};
+    use crate::solver::{
+        lexicon::{Lexicon, LEXICON_PATH},
+        solutions::{SolveParams, Solver},
+    };
+
+    #[test]
+    fn verify_solutions() {
+        let letters = "rvheaipnwgmo";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+
+        let verified = verify(letters, &["improving", "german", "nowhere"], &lexicon).unwrap();
+        assert_eq!(verified.words().len(), 3);
+        for (word, trajectory) in verified.words().iter().zip(verified.trajectories()) {
+            let traced: String = trajectory
+                .iter()
+                .map(|i| letters.chars().nth(*i).unwrap())
+                .collect();
+            assert_eq!(&traced, word);
+        }
+        let trajectories = verified.trajectories();
+        assert_eq!(trajectories[0].last(), trajectories[1].first());
+
+        // Each way of failing is reported
+        let no_words: [&str; 0] = [];
+        assert_eq!(
+            verify(letters, &no_words, &lexicon),
+            Err(VerifyError::NoWords)
+        );
+        assert_eq!(
+            verify(letters, &["pavo"], &lexicon),
+            Err(VerifyError::NotInLexicon("pavo".to_string()))
+        );
+        assert_eq!(
+            verify(letters, &["ear"], &lexicon),
+            Err(VerifyError::Untraceable("ear".to_string()))
+        );
+        assert_eq!(
+            verify(letters, &["improving", "nowhere"], &lexicon),
+            Err(VerifyError::BrokenChain("nowhere".to_string()))
+        );
+        assert_eq!(
+            verify(letters, &["improving", "german"], &lexicon),
+            Err(VerifyError::Uncovered(vec![
+                "h".to_string(),
+                "w".to_string()
+            ]))
+        );
+
+        // The solver's own solutions check out
+        let solution = Solver::solve(SolveParams::new(letters).unwrap(), &lexicon).unwrap();
+        assert!(verify(letters, solution.words(), &lexicon).is_ok());
+    }
+
+    #[test]
+    fn verify_repeated_letters() {
+        /*
+        --------------------------------
+          U I G
+        M       A
+        A       A
+        I       N
+          P B G
+        --------------------------------
+        */
+        // "map" only covers both A's between the two words played through it
+        let letters = "uigaangbpiam";
+        let lexicon = Lexicon::new(LEXICON_PATH).unwrap();
+        let verified = verify(letters, &["map"], &lexicon);
+        assert!(matches!(verified, Err(VerifyError::Uncovered(_))));
+    }
+}
@@ -12,6 +12,11 @@
 //! `WordTrajectories` is an iterator that yields possible word trajectories
 //! given a word and available letters.
 //!
+//! ## Wildcards
+//!
+//! A `?` on the board matches any letter. It still obeys the side rule, and
+//! each trajectory records the letters its wildcards stood for.
+//!
 //! ## Example
 //!
 //! ```rust
@@ -29,15 +34,23 @@
 //! ```
 //!
 
+use super::topology::BoardTopology;
 use ::rand::Rng;
 use rand::distributions::WeightedIndex;
 use rand::prelude::*;
 use std::collections::{HashMap, VecDeque};
 
+/// A board position holding this matches any one letter
+pub const WILDCARD: char = '?';
+
 /// Represents a trajectory of word formation.
 #[derive(Debug, Clone)]
 pub struct WordTrajectory {
     indices: Vec<usize>,
+    /// The wildcard positions played, each with the letter it stood for.
+    substitutions: Vec<(usize, char)>,
+    /// The number of bytes of the word spelled so far.
+    spelled: usize,
 }
 
 impl WordTrajectory {
@@ -45,6 +58,8 @@ impl WordTrajectory {
     pub fn new() -> WordTrajectory {
         WordTrajectory {
             indices: Vec::new(),
+            substitutions: Vec::new(),
+            spelled: 0,
         }
     }
 
@@ -60,7 +75,24 @@ impl WordTrajectory {
     pub fn add_index(&self, i: usize) -> WordTrajectory {
         let mut next_vec = self.indices.clone();
         next_vec.push(i);
-        WordTrajectory { indices: next_vec }
+        WordTrajectory {
+            indices: next_vec,
+            substitutions: self.substitutions.clone(),
+            spelled: self.spelled,
+        }
+    }
+
+    /// Adds a wildcard position to the trajectory, standing for `letter`.
+    pub fn add_wildcard(&self, i: usize, letter: char) -> WordTrajectory {
+        let mut next = self.add_index(i);
+        next.substitutions.push((i, letter));
+        next
+    }
+
+    /// Marks the last step as spelling `bytes` more of the word.
+    fn spelling(mut self, bytes: usize) -> WordTrajectory {
+        self.spelled += bytes;
+        self
     }
 
     /// Returns the number of indices in the trajectory.
@@ -80,6 +112,12 @@ impl WordTrajectory {
     pub fn indices(&self) -> &Vec<usize> {
         &self.indices
     }
+
+    /// Returns each wildcard position played, in order, with the letter it
+    /// stood for.
+    pub fn substitutions(&self) -> &Vec<(usize, char)> {
+        &self.substitutions
+    }
 }
 
 type LetterIndices = HashMap<char, Vec<usize>>;
@@ -90,7 +128,12 @@ type LetterIndices = HashMap<char, Vec<usize>>;
 pub struct WordTrajectories<'a> {
     word: &'a str,
     letters: LetterIndices,
+    /// The tile at each position, if any holds more than one letter
+    tiles: Option<&'a [String]>,
+    /// The length of each side of a classic board, used without a topology
     s: usize,
+    /// The shape of the board, if not the classic one
+    topology: Option<&'a BoardTopology>,
     queue: VecDeque<WordTrajectory>,
 }
 
@@ -100,22 +143,68 @@ impl<'a> WordTrajectories<'a> {
         return WordTrajectories {
             word: word,
             letters: Self::_letter_indices(letters),
-            s: letters.len() / 4,
+            tiles: None,
+            s: (letters.chars().count() / 4).max(1),
+            topology: None,
             queue: VecDeque::from([WordTrajectory::new()]),
         };
     }
 
+    /// Create a new collection of trajectories on a board of the given shape
+    pub fn with_topology(
+        word: &'a str,
+        letters: &'a str,
+        topology: &'a BoardTopology,
+    ) -> WordTrajectories<'a> {
+        WordTrajectories {
+            word,
+            letters: Self::_letter_indices(letters),
+            tiles: None,
+            s: 1,
+            topology: Some(topology),
+            queue: VecDeque::from([WordTrajectory::new()]),
+        }
+    }
+
+    /// Create a new collection of trajectories on a board of tiles, each of
+    /// which may hold several letters (such as "qu")
+    pub fn with_tiles(
+        word: &'a str,
+        tiles: &'a [String],
+        topology: &'a BoardTopology,
+    ) -> WordTrajectories<'a> {
+        let mut letters: LetterIndices = HashMap::new();
+        for (i, tile) in tiles.iter().enumerate() {
+            if let Some(first) = tile.chars().next() {
+                letters.entry(first).or_default().push(i);
+            }
+        }
+        WordTrajectories {
+            word,
+            letters,
+            tiles: Some(tiles),
+            s: 1,
+            topology: Some(topology),
+            queue: VecDeque::from([WordTrajectory::new()]),
+        }
+    }
+
     /// BFS visit behavior
     pub fn _visit(
         word: &str,
         letters: &LetterIndices,
+        tiles: Option<&[String]>,
         s: usize,
+        topology: Option<&BoardTopology>,
         queue: &mut VecDeque<WordTrajectory>,
     ) -> Option<WordTrajectory> {
         // First, pop the latest Trajectory to search
         if let Some(trajectory) = queue.pop_front() {
+            // How much of the word the trajectory spells
+            let consumed = trajectory.spelled;
+
             // Success condition: no letters left
-            if word.len() == trajectory.len() {
+            if word.len() == consumed {
                 return Some(trajectory);
             }
 
@@ -124,28 +213,61 @@ impl<'a> WordTrajectories<'a> {
             // next steps
 
             // Dissect the word into the first char and the suffix slice
-            let cur_char_idx = trajectory.len();
-            let c0 = word.chars().nth(cur_char_idx)?;
+            let rest = &word[consumed..];
+            let c0 = rest.chars().next()?;
+            // Positions holding the letter, and wildcards which match any
+            let mut next_locs: Vec<(usize, bool)> = letters
+                .get(&c0)
+                .into_iter()
+                .flatten()
+                .map(|i| (*i, false))
+                .collect();
+            if c0 != WILDCARD {
+                next_locs.extend(
+                    letters
+                        .get(&WILDCARD)
+                        .into_iter()
+                        .flatten()
+                        .map(|i| (*i, true)),
+                );
+            }
             // If the letter isn't in the game, immediately fail
-            if !letters.contains_key(&c0) {
+            if next_locs.is_empty() {
                 return None;
             }
+            next_locs.sort();
 
             // Iterate over the possible next indices
-            let next_locs = letters.get(&c0)?;
-
-            for next_loc in next_locs {
+            for (next_loc, wild) in next_locs {
+                // A tile of several letters must match all of them
+                if !wild && tiles.is_some_and(|tiles| !rest.starts_with(tiles[next_loc].as_str())) {
+                    continue;
+                }
                 // If we have a trajectory, check if the next
                 // letter can be placed
                 if let Some(cur_loc) = trajectory.last() {
-                    if cur_loc / s == next_loc / s {
+                    let forbidden = match topology {
+                        Some(topology) => topology.forbids(cur_loc, next_loc),
+                        None => cur_loc / s == next_loc / s,
+                    };
+                    if forbidden {
                         continue;
                     }
                 }
                 // Otherwise, queue up the extended trajectories
                 // (Preferentially push these to the front of the
                 // queue so we search deeper before broader)
-                queue.push_front(trajectory.add_index(*next_loc));
+                // A wildcard spells a single letter, however long its tile
+                if wild {
+                    queue.push_front(
+                        trajectory
+                            .add_wildcard(next_loc, c0)
+                            .spelling(c0.len_utf8()),
+                    );
+                } else {
+                    let bytes = tiles.map_or(c0.len_utf8(), |tiles| tiles[next_loc].len());
+                    queue.push_front(trajectory.add_index(next_loc).spelling(bytes));
+                }
             }
         }
         return None;
@@ -179,7 +301,14 @@ impl<'a> Iterator for WordTrajectories<'a> {
         while !self.queue.is_empty() {
             // Iterate until we get a valid word, then
             // immediately return - state maintained by the queue
-            if let Some(success) = Self::_visit(self.word, &self.letters, self.s, &mut self.queue) {
+            if let Some(success) = Self::_visit(
+                self.word,
+                &self.letters,
+                self.tiles,
+                self.s,
+                self.topology,
+                &mut self.queue,
+            ) {
                 return Some(success);
             }
         }
@@ -256,10 +385,33 @@ pub fn can_make_word<'a>(word: &'a str, letters: &'a str) -> WordTrajectories<'a
     WordTrajectories::new(word, letters)
 }
 
+/// Determines if a word can be formed from the given tiles, laid out on a
+/// board of the given shape.
+pub fn can_make_word_tiles<'a>(
+    word: &'a str,
+    tiles: &'a [String],
+    topology: &'a BoardTopology,
+) -> WordTrajectories<'a> {
+    WordTrajectories::with_tiles(word, tiles, topology)
+}
+
+/// Determines if a word can be formed using the given letters, laid out on a
+/// board of the given shape.
+pub fn can_make_word_on<'a>(
+    word: &'a str,
+    letters: &'a str,
+    topology: &'a BoardTopology,
+) -> WordTrajectories<'a> {
+    WordTrajectories::with_topology(word, letters, topology)
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::{can_make_word, random_string, WordTrajectory};
+    use super::{
+        can_make_word, can_make_word_on, can_make_word_tiles, random_string, WordTrajectory,
+    };
+    use crate::solver::topology::BoardTopology;
     use std::iter::zip;
 
     #[test]
@@ -430,4 +582,117 @@ mod tests {
         let word = "map";
         let _ = can_make_word(word, letters);
     }
+
+    #[test]
+    fn topology_can_make_word() {
+        /*
+        -------------
+           R V
+         O     H
+        M       E
+        G       A
+         W     I
+           N P
+        -------------
+        */
+        let letters = "rvheaipnwgmo";
+        let hexagon = BoardTopology::from_sides(&["rv", "he", "ai", "pn", "wg", "mo"]).unwrap();
+
+        // E and A share a side on the classic board, but not on the hexagon
+        assert!(can_make_word("ear", letters).next().is_none());
+        let trajectories: Vec<_> = can_make_word_on("ear", letters, &hexagon).collect();
+        assert_eq!(trajectories.len(), 1);
+        assert_eq!(*trajectories[0].indices(), [3, 4, 0]);
+
+        // ... while H and E share a side on the hexagon only
+        assert!(can_make_word("he", letters).next().is_some());
+        assert!(can_make_word_on("he", letters, &hexagon).next().is_none());
+
+        // Sides of different lengths
+        let letters = "abcdefghi";
+        let triangle = BoardTopology::from_sides(&["abcd", "ef", "ghi"]).unwrap();
+        assert!(can_make_word_on("die", letters, &triangle).next().is_some());
+        assert!(can_make_word_on("dab", letters, &triangle).next().is_none());
+    }
+
+    #[test]
+    fn tiles_can_make_word() {
+        /*
+        -------------------
+           TH  R   I
+        T             QU
+        C             E
+        S             A
+           O   N   L
+        -------------------
+        */
+        let tiles: Vec<String> = ["th", "r", "i", "qu", "e", "a", "l", "n", "o", "s", "c", "t"]
+            .map(String::from)
+            .to_vec();
+        let square = BoardTopology::square(tiles.len());
+
+        let keys: [(&str, &[&[usize]]); 4] = [
+            ("quote", &[&[3, 8, 11, 4]]),
+            ("the", &[&[0, 4]]),
+            ("that", &[&[0, 5, 11]]),
+            // A tile is played whole, or not at all
+            ("qat", &[]),
+        ];
+        for (word, key) in keys {
+            let trajectories: Vec<_> = can_make_word_tiles(word, &tiles, &square).collect();
+            assert_eq!(trajectories.len(), key.len());
+            for (traj, key) in zip(trajectories, key) {
+                assert_eq!(traj.indices(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn wildcard_tiles_can_make_word() {
+        let tiles: Vec<String> = "?bcdefghijkl".chars().map(String::from).collect();
+        let square = BoardTopology::square(tiles.len());
+
+        // A wildcard tile is one byte, but may stand for a letter of several
+        assert_eq!(can_make_word_tiles("éb", &tiles, &square).count(), 0);
+        let trajectories: Vec<_> = can_make_word_tiles("éd", &tiles, &square).collect();
+        assert_eq!(trajectories.len(), 1);
+        assert_eq!(trajectories[0].indices(), &[0, 3]);
+        assert_eq!(trajectories[0].substitutions(), &[(0, 'é')]);
+    }
+
+    #[test]
+    fn wildcard_can_make_word() {
+        // The M of the example board is replaced by a wildcard
+        let letters = "uigaangbpia?";
+        let square = BoardTopology::square(12);
+
+        // "map" can only start on the wildcard, which may stand in for the P too
+        let indices: [&[usize]; 4] = [&[11, 4, 11], &[11, 4, 8], &[11, 3, 11], &[11, 3, 8]];
+        let substitutions: [&[(usize, char)]; 4] = [
+            &[(11, 'm'), (11, 'p')],
+            &[(11, 'm')],
+            &[(11, 'm'), (11, 'p')],
+            &[(11, 'm')],
+        ];
+        let trajectories: Vec<_> = can_make_word("map", letters).collect();
+        assert_eq!(trajectories.len(), indices.len());
+        for (traj, (indices, substitutions)) in zip(trajectories, zip(indices, substitutions)) {
+            assert_eq!(traj.indices(), indices);
+            assert_eq!(traj.substitutions(), substitutions);
+        }
+
+        // The wildcard still obeys the side rule
+        for traj in can_make_word("gap", letters) {
+            for pair in traj.indices().windows(2) {
+                assert!(!square.forbids(pair[0], pair[1]));
+            }
+        }
+        assert!(can_make_word("gap", letters).any(|t| t.substitutions().is_empty()));
+
+        // A letter not on the board can only be spelled with the wildcard
+        assert!(
+            can_make_word("bingo", letters).all(|t| t.substitutions().last() == Some(&(11, 'o')))
+        );
+        assert_eq!(can_make_word("boo", letters).count(), 0);
+    }
 }
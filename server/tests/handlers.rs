@@ -1,6 +1,16 @@
 // use poem::web::TestRequest;
-use letter_boxed::handlers::handle_solve;
-use poem::test::TestClient;
+use letter_boxed::handlers::{
+    handle_enumerate, handle_hint, handle_infer, handle_solve, handle_solve_stream,
+    handle_validate, spawn_cancellable, EnumerateParams,
+};
+use letter_boxed::solver::strategies::CancelToken;
+use poem::{http::StatusCode, test::TestClient, web::sse::Event};
+use serde_json::Value;
+use std::{
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+use tokio_stream::StreamExt;
 
 #[tokio::test]
 async fn test_solve_handler() {
@@ -14,3 +24,649 @@ async fn test_solve_handler() {
         .await;
     resp.assert_status_is_ok();
 }
+
+#[tokio::test]
+async fn test_solve_handler_min_words() {
+    let letters = "rvheaipnwgmo";
+    let resp = TestClient::new(handle_solve)
+        .get("/solve")
+        .query("letters", &letters)
+        .query("mode", &"min_words")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    json.value()
+        .object()
+        .get("meta")
+        .object()
+        .get("status")
+        .assert_string("SUCCESS");
+    json.value()
+        .object()
+        .get("solution")
+        .object()
+        .get("optimality")
+        .assert_string("PROVEN");
+}
+
+#[tokio::test]
+async fn test_solve_handler_perfect() {
+    let resp = TestClient::new(handle_solve)
+        .get("/solve")
+        .query("letters", &"caitnsolgbre")
+        .query("mode", &"perfect")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    json.value()
+        .object()
+        .get("solution")
+        .object()
+        .get("words")
+        .assert_string_array(&["tables", "scoring"]);
+}
+
+#[tokio::test]
+async fn test_solve_handler_pangram() {
+    let resp = TestClient::new(handle_solve)
+        .get("/solve")
+        .query("letters", &"cmlopietrnay")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let result = json.value().object();
+    result.get("pangram").assert_bool(true);
+    let pangrams = result.get("pangrams").array();
+    pangrams.assert_len(1);
+    pangrams
+        .get(0)
+        .object()
+        .get("word")
+        .assert_string("complimentary");
+}
+
+#[tokio::test]
+async fn test_solve_handler_constraints() {
+    let client = TestClient::new(handle_solve);
+    let resp = client
+        .get("/solve")
+        .query("letters", &"rvheaipnwgmo")
+        .query("required", &"hero")
+        .query("banned", &"improving")
+        .query("max_word_len", &8)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let words = json.value().object().get("solution").object().get("words");
+    assert!(words.array().iter().any(|w| w.string() == "hero"));
+    assert!(words.array().iter().all(|w| w.string().len() <= 8));
+
+    // Required words must be allowed
+    let resp = client
+        .get("/solve")
+        .query("letters", &"rvheaipnwgmo")
+        .query("required", &"hero")
+        .query("banned", &"hero")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+
+    // ...and playable on the board, streamed or not
+    let resp = client
+        .get("/solve")
+        .query("letters", &"rvheaipnwgmo")
+        .query("required", &"zebra")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    let resp = TestClient::new(handle_solve_stream)
+        .get("/solve/stream")
+        .query("letters", &"rvheaipnwgmo")
+        .query("required", &"zebra")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+
+    // The game can be held to a number of words
+    let resp = client
+        .get("/solve")
+        .query("letters", &"rvheaipnwgmo")
+        .query("max_game_words", &2)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.json()
+        .await
+        .value()
+        .object()
+        .get("meta")
+        .object()
+        .get("status")
+        .object()
+        .get("FAIL")
+        .assert_string("GENERAL");
+}
+
+#[tokio::test]
+async fn test_solve_handler_played() {
+    let letters = "rvheaipnwgmo";
+    let client = TestClient::new(handle_solve);
+    let resp = client
+        .get("/solve")
+        .query("letters", &letters)
+        .query("mode", &"min_words")
+        .query("played", &"improving,german")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let solution = json.value().object().get("solution").object();
+    solution.get("words").array().assert_len(1);
+
+    // Empty entries in the list are skipped
+    let resp = client
+        .get("/solve")
+        .query("letters", &letters)
+        .query("mode", &"min_words")
+        .query("played", &"improving,,german")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let solution = json.value().object().get("solution").object();
+    solution.get("words").array().assert_len(1);
+
+    // Words that don't chain are rejected
+    let resp = client
+        .get("/solve")
+        .query("letters", &letters)
+        .query("played", &"improving,nowhere")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_solve_handler_timeout() {
+    let letters = "rvheaipnwgmo";
+    let resp = TestClient::new(handle_solve)
+        .get("/solve")
+        .query("letters", &letters)
+        .query("max_duration", &0)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let result = json.value().object();
+    result
+        .get("meta")
+        .object()
+        .get("status")
+        .object()
+        .get("PARTIAL")
+        .assert_string("TIMEOUT");
+
+    // Out of time before the first word, the best path is a single letter
+    result
+        .get("solution")
+        .object()
+        .get("words")
+        .array()
+        .assert_len(0);
+    result
+        .get("uncovered")
+        .array()
+        .assert_len(letters.len() - 1);
+}
+
+#[tokio::test]
+async fn test_solve_handler_unsolvable() {
+    let resp = TestClient::new(handle_solve)
+        .get("/solve")
+        .query("letters", &"aeiqxzjvkwyf")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let result = json.value().object();
+    result
+        .get("meta")
+        .object()
+        .get("status")
+        .object()
+        .get("FAIL")
+        .assert_string("GENERAL");
+
+    // The failure is explained
+    let diagnosis = result.get("diagnosis").object();
+    diagnosis.get("untouched").assert_string_array(&["z"]);
+    diagnosis.get("ends_only").assert_string_array(&["q", "x"]);
+    diagnosis.get("best_coverage").assert_i64(8);
+    diagnosis.get("complete").assert_bool(true);
+}
+
+#[tokio::test]
+async fn test_solve_handler_adjacency() {
+    let client = TestClient::new(handle_solve);
+    let resp = client
+        .get("/solve")
+        .query("letters", &"rvheaipnwgmo")
+        .query("adjacency", &"adjacent_sides")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    json.value()
+        .object()
+        .get("meta")
+        .object()
+        .get("status")
+        .object()
+        .get("FAIL")
+        .assert_string("GENERAL");
+
+    // Malformed pairs are rejected
+    let resp = client
+        .get("/solve")
+        .query("letters", &"rvheaipnwgmo")
+        .query("forbidden", &"0-6,5")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_solve_handler_tiles() {
+    let resp = TestClient::new(handle_solve)
+        .get("/solve")
+        .query("letters", &"thriquealnosct")
+        .query("tiles", &"th,r,i,qu,e,a,l,n,o,s,c,t")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let result = json.value().object();
+    result
+        .get("meta")
+        .object()
+        .get("status")
+        .assert_string("SUCCESS");
+    assert!(!result
+        .get("solution")
+        .object()
+        .get("words")
+        .array()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_solve_handler_wildcard() {
+    let resp = TestClient::new(handle_solve)
+        .get("/solve")
+        .query("letters", &"rvheaipn?gmo")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let result = json.value().object();
+    result
+        .get("meta")
+        .object()
+        .get("status")
+        .assert_string("SUCCESS");
+    // Each wildcard played is reported
+    let substitutions = result.get("substitutions").array();
+    assert!(!substitutions.is_empty());
+    for substitution in substitutions.iter() {
+        substitution.object().get("position").assert_i64(8);
+    }
+}
+
+#[tokio::test]
+async fn test_solve_stream_handler() {
+    let letters = "rvheaipnwgmo";
+    let resp = TestClient::new(handle_solve_stream)
+        .get("/solve/stream")
+        .query("letters", &letters)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let events: Vec<(String, Value)> = resp
+        .sse_stream()
+        .map(|event| match event {
+            Event::Message { event, data, .. } => (event, serde_json::from_str(&data).unwrap()),
+            event => panic!("Unexpected event {:?}", event),
+        })
+        .collect()
+        .await;
+
+    // Improving solutions, ending with a proven one, then the final status
+    let (done, solutions) = events.split_last().unwrap();
+    assert_eq!(done.0, "done");
+    assert_eq!(done.1["status"], "SUCCESS");
+    assert!(!solutions.is_empty());
+    let mut words = usize::MAX;
+    for (event, data) in solutions {
+        assert_eq!(event, "solution");
+        let len = data["solution"]["words"].as_array().unwrap().len();
+        assert!(len <= words);
+        words = len;
+    }
+    assert_eq!(solutions.last().unwrap().1["optimality"], "PROVEN");
+}
+
+#[tokio::test]
+async fn test_enumerate_handler() {
+    let letters = "caitnsolgbre";
+    let client = TestClient::new(handle_enumerate);
+
+    let resp = client
+        .get("/enumerate")
+        .query("letters", &letters)
+        .query("max_words", &2)
+        .query("page_size", &3)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let page = json.value().object();
+    page.get("page").assert_i64(0);
+    page.get("has_more").assert_bool(true);
+    page.get("complete").assert_bool(true);
+    page.get("solutions").array().assert_len(3);
+
+    // The next page continues where the first left off
+    let first = page.get("solutions").array().get(2).object().get("words");
+    let first = first.array().get(0).string().to_string();
+    let resp = client
+        .get("/enumerate")
+        .query("letters", &letters)
+        .query("max_words", &2)
+        .query("page_size", &2)
+        .query("page", &1)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let solutions = json.value().object().get("solutions").array();
+    assert_eq!(
+        solutions
+            .get(0)
+            .object()
+            .get("words")
+            .array()
+            .get(0)
+            .string(),
+        first
+    );
+
+    // An enumeration cut short by a limit says so
+    let resp = client
+        .get("/enumerate")
+        .query("letters", &letters)
+        .query("max_words", &2)
+        .query("max_expansions", &3)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    json.value().object().get("complete").assert_bool(false);
+
+    // Pages out of range, and enumerations too large, are rejected
+    let page = (EnumerateParams::MAX_OFFSET + 1).to_string();
+    let size = (EnumerateParams::MAX_PAGE_SIZE + 1).to_string();
+    let words = (EnumerateParams::MAX_WORDS + 1).to_string();
+    for (key, value) in [("page", &page), ("page_size", &size), ("max_words", &words)] {
+        let resp = client
+            .get("/enumerate")
+            .query("letters", &letters)
+            .query(key, value)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_enumerate_handler_no_words() {
+    // Enumerating solutions of no words finds none, rather than failing
+    let resp = TestClient::new(handle_enumerate)
+        .get("/enumerate")
+        .query("letters", &"caitnsolgbre")
+        .query("max_words", &0)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let page = json.value().object();
+    page.get("solutions").array().assert_len(0);
+    page.get("has_more").assert_bool(false);
+}
+
+#[tokio::test]
+async fn test_validate_handler() {
+    let letters = "rvheaipnwgmo";
+    let client = TestClient::new(handle_validate);
+    let resp = client
+        .get("/validate")
+        .query("letters", &letters)
+        .query("words", &"improving,nowhere")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let report = json.value().object();
+    report.get("valid").assert_bool(false);
+    let words = report.get("words").array();
+    words.assert_len(2);
+    words.get(0).object().get("errors").array().assert_len(0);
+    words
+        .get(1)
+        .object()
+        .get("errors")
+        .array()
+        .get(0)
+        .assert_string("BrokenChain");
+    assert!(report.get_opt("verified").is_none());
+
+    // A solved play comes back confirmed by the verifier
+    let resp = client
+        .get("/validate")
+        .query("letters", &letters)
+        .query("words", &"improving,german,nowhere")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let report = json.value().object();
+    report.get("solved").assert_bool(true);
+    report
+        .get("verified")
+        .object()
+        .get("trajectories")
+        .array()
+        .assert_len(3);
+
+    // Invalid boards are rejected outright
+    let resp = client
+        .get("/validate")
+        .query("letters", &"abc")
+        .query("words", &"cab")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+
+    // Words are checked on the sides given, where I and N share a side
+    let resp = client
+        .get("/validate")
+        .query("letters", &letters)
+        .query("sides", &"rv,hea,ipnw,gmo")
+        .query("words", &"improving")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let words = json.value().object().get("words").array();
+    words
+        .get(0)
+        .object()
+        .get("errors")
+        .array()
+        .get(0)
+        .assert_string("SameSideAdjacency");
+}
+
+#[tokio::test]
+async fn test_hint_handler() {
+    let letters = "rvheaipnwgmo";
+    let client = TestClient::new(handle_hint);
+    let resp = client
+        .get("/hint")
+        .query("letters", &letters)
+        .query("played", &"improving")
+        .query("level", &"pattern")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let hint = json.value().object();
+    hint.get("level").assert_string("pattern");
+    hint.get("start").assert_string("g");
+    assert!(hint.get("pattern").string().starts_with("g_"));
+    assert!(hint.get_opt("word").is_none());
+
+    // A finished game has nothing left to hint at
+    let resp = client
+        .get("/hint")
+        .query("letters", &letters)
+        .query("played", &"improving,german,nowhere")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.json().await.value().assert_null();
+}
+
+#[tokio::test]
+async fn test_hint_handler_sides() {
+    let client = TestClient::new(handle_hint);
+
+    // Played words are checked on the sides given
+    let resp = client
+        .get("/hint")
+        .query("letters", &"rvheaipnwgmo")
+        .query("sides", &"rv,hea,ipnw,gmo")
+        .query("played", &"improving")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    let resp = client
+        .get("/hint")
+        .query("letters", &"rvheaipnwgmo")
+        .query("sides", &"rv,hea,ipnw,gmo")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+
+    // Sides must spell the board
+    let resp = client
+        .get("/hint")
+        .query("letters", &"rvheaipnwgmo")
+        .query("sides", &"rvh,eai")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_infer_handler() {
+    let client = TestClient::new(handle_infer);
+    let resp = client
+        .get("/infer")
+        .query("letters", &"rvh?aipnwgm?")
+        .query("words", &"improving,nowhere")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await;
+    let found = json.value().object();
+    found.get("complete").assert_bool(true);
+    let inferences = found.get("inferences").array();
+    inferences.assert_len(2);
+    inferences
+        .get(0)
+        .object()
+        .get("board")
+        .assert_string("rvheaipnwgmo");
+
+    // A mostly unknown board is refused, and a search over many unknowns
+    // stops at its limits
+    let resp = client
+        .get("/infer")
+        .query("letters", &"????????????")
+        .query("words", &"improving")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    let resp = client
+        .get("/infer")
+        .query("letters", &"rv??ai??wg??")
+        .query("words", &"improving,nowhere")
+        .query("max_expansions", &10)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.json()
+        .await
+        .value()
+        .object()
+        .get("complete")
+        .assert_bool(false);
+
+    // Words that can't be played whatever the unknown letters
+    let resp = client
+        .get("/infer")
+        .query("letters", &"rvh?aipnwgm?")
+        .query("words", &"zzz")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Words are traced on the sides given, where I and N share a side and
+    // the remembered words can't both be played
+    let resp = client
+        .get("/infer")
+        .query("letters", &"rvh?aipnwgm?")
+        .query("sides", &"rv,h?a,ipnw,gm?")
+        .query("words", &"improving,nowhere")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.json()
+        .await
+        .value()
+        .object()
+        .get("inferences")
+        .array()
+        .assert_len(0);
+}
+
+#[tokio::test]
+async fn test_dropped_request_cancels() {
+    let (tx, rx) = mpsc::channel();
+    let request = spawn_cancellable(move |cancel: CancelToken| {
+        // Stands in for a long search, checking the token as it goes
+        let start = Instant::now();
+        while !cancel.is_cancelled() && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        tx.send(cancel.is_cancelled()).unwrap();
+    });
+
+    // The client goes away before the search is done
+    let _ = tokio::time::timeout(Duration::from_millis(50), request).await;
+    assert!(rx.recv_timeout(Duration::from_secs(5)).unwrap());
+}